@@ -0,0 +1,179 @@
+use crate::game::{
+    map::Automap, parser::Parser, replay::Replay, title, transcript::Format, Game, GameRng, World,
+};
+use crate::mini::{self, guess::Guess};
+use crate::{protocol, server};
+use rand::SeedableRng;
+use std::io::{stdin, stdout};
+use std::net::TcpListener;
+use std::time::Duration;
+
+/// Get the value of a command line option, given as `--name value` or `--name=value`.
+fn option(args: &[String], name: &str) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, a)| {
+        if a == name {
+            args.get(i + 1).cloned()
+        } else {
+            a.strip_prefix(name)?
+                .strip_prefix('=')
+                .map(|v| v.to_string())
+        }
+    })
+}
+
+/// Is the flag given, on its own?
+fn flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|a| a == name)
+}
+
+/// Do what the command line asks, in the given world. With no options, play it at the terminal.
+pub fn run(args: &[String], world: World) {
+    // --server runs a game for every player who connects. --server=ADDRESS to listen elsewhere.
+    if args
+        .iter()
+        .any(|a| a == "--server" || a.starts_with("--server="))
+    {
+        serve(args, world);
+    } else if flag(args, "--guess") {
+        guess(args);
+    } else if flag(args, "--json") {
+        json(args, world);
+    } else if flag(args, "--dump-map") {
+        dump_map(world);
+    } else {
+        play(args, world);
+    }
+}
+
+/// Listen for players, and give each a game.
+fn serve(args: &[String], world: World) {
+    let address = option(args, "--server")
+        .filter(|a| !a.starts_with("--"))
+        .unwrap_or(server::DEFAULT_ADDRESS.to_string());
+    let mut config = server::Config::default();
+    if let Some(n) = option(args, "--max-players").and_then(|n| n.parse().ok()) {
+        config.max_players = n;
+    }
+    if let Some(secs) = option(args, "--idle").and_then(|n| n.parse().ok()) {
+        config.idle = Duration::from_secs(secs);
+    }
+    // --shared puts everyone in the same world.
+    config.shared = flag(args, "--shared");
+    match TcpListener::bind(&address) {
+        Ok(listener) => {
+            eprintln!(
+                "Listening on {}. Connect with: telnet {}",
+                address,
+                address.replace(':', " ")
+            );
+            server::serve(listener, world, config);
+        }
+        Err(e) => eprintln!("Unable to listen on {}: {}", address, e),
+    }
+}
+
+/// Play the word guessing game on its own.
+fn guess(args: &[String]) {
+    let seed = option(args, "--seed")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(rand::random);
+    let mut rng = GameRng::seed_from_u64(seed);
+    let mut guess = Guess::new();
+    mini::run(&mut guess, &mut rng, &mut Parser::default());
+}
+
+/// For programs: one JSON request per line in, one JSON response per turn out.
+fn json(args: &[String], world: World) {
+    let mut game = Game::new(world);
+    if let Some(seed) = option(args, "--seed").and_then(|s| s.parse().ok()) {
+        game.set_seed(seed);
+    }
+    if let Err(e) = protocol::run(&mut game, stdin().lock(), stdout()) {
+        eprintln!("Unable to talk JSON: {}", e);
+    }
+}
+
+/// For authors: draw the whole world and quit.
+fn dump_map(world: World) {
+    let (atlas, _) = world();
+    print!(
+        "{}",
+        Automap::from_world(&atlas).render(&atlas.here(), None)
+    );
+}
+
+/// Play at the terminal.
+fn play(args: &[String], world: World) {
+    // --script records a transcript from the start. --script=md or --script=html also exports it.
+    let script = args.iter().find_map(|a| match a.as_str() {
+        "--script" => Some(Some(Format::Text)),
+        _ => a.strip_prefix("--script=").map(Format::from_name),
+    });
+    if let Some(None) = script {
+        eprintln!("--script format must be text, markdown or html");
+        return;
+    }
+
+    // --replay plays back a recorded session. --replay-until stops after that many commands.
+    let replay = match option(args, "--replay").map(|path| Replay::load(&path)) {
+        None => None,
+        Some(Ok(mut replay)) => {
+            if let Some(turns) = option(args, "--replay-until").and_then(|n| n.parse().ok()) {
+                replay.truncate(turns);
+            }
+            Some(replay)
+        }
+        Some(Err(e)) => {
+            eprintln!("Unable to load the replay: {}", e);
+            return;
+        }
+    };
+
+    title::print();
+
+    let mut game = Game::new(world);
+    // --seed N plays game number N again.
+    if let Some(seed) = option(args, "--seed") {
+        match seed.parse() {
+            Ok(seed) => game.set_seed(seed),
+            Err(_) => {
+                eprintln!("--seed must be a number");
+                return;
+            }
+        }
+    }
+    if let Some(Some(format)) = script {
+        game.start_script(format);
+    }
+    if let Some(replay) = replay {
+        game.set_seed(replay.seed);
+        game.replay(replay.lines);
+    }
+    // --record saves every command and the seed, so the session can be replayed.
+    if let Some(path) = option(args, "--record") {
+        if let Err(e) = game.record(&path) {
+            eprintln!("Unable to record to {}: {}", path, e);
+            return;
+        }
+    }
+    game.run();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_option() {
+        let args = args("--seed 42 --server=0.0.0.0:23 --shared");
+        assert_eq!(option(&args, "--seed"), Some(String::from("42")));
+        assert_eq!(option(&args, "--server"), Some(String::from("0.0.0.0:23")));
+        assert_eq!(option(&args, "--record"), None);
+        assert!(flag(&args, "--shared"));
+        assert!(!flag(&args, "--json"));
+    }
+}
@@ -1,43 +1,178 @@
-use super::{Action, GameContext, GameObject, Handled, Location, Notify};
+use super::{
+    Action, Direction, Effect, Event, Factory, GameContext, GameObject, Handled, Health, Location,
+    Lockable, Notify, Weapon,
+};
+use crate::mini::{MiniGame, Outcome};
+use rand::{rngs::StdRng, SeedableRng};
 use std::cell::{Ref, RefCell, RefMut};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 // TODO: implement non-here context for the action.
 // Can I take an object from the cupboard, if I'm not standing in the kicthen?
 // Is the book in the bookshelf, or in the library itself?
 // Currently, the bookshelf will move a book into the library when opened.
-// I can look into a breadbox, but I can't interact with the bread until it is moved to the kitchen.
 
 pub static INVENTORY: &str = "__inv";
+/// The player, when there's only one.
+pub static PLAYER: &str = "";
 pub static NOWHERE: &str = "__nowhere";
 pub static _GLOBAL: &str = "__global";
 
+/// Get the name to show the player. Objects made during the game go by their kind. (See GameAtlas::spawn.)
+pub fn kind_of(name: &str) -> &str {
+    name.split_once('#').map_or(name, |(kind, _)| kind)
+}
+
 /// The game atlas controls all objects in the game.
 /// It is responsible for adding, removing, and moving objects.
 /// It also provides a context for the parser.
 /// ! The game atlas is the only object that can move objects.
-#[derive(Default)]
+/// Objects are kept in name order, so every game (and every replay) sees them in the same order.
+/// ! All random numbers in the game come from the atlas, so a game can be replayed from its seed.
+/// Several players can share an atlas. Each has their own location and inventory, but every object is shared.
+/// Only one player acts at a time: "here" and "inventory" always mean theirs.
 pub struct GameAtlas {
-    here: String,
-    atlas: HashMap<String, RefCell<Box<dyn GameObject>>>,
+    player: String,                    // the player taking their turn
+    players: BTreeMap<String, String>, // where each player is
+    news: Vec<(String, String)>,       // for other players: who to tell, what to say
+    atlas: BTreeMap<String, RefCell<Box<dyn GameObject>>>,
+    seed: u64,   // seed for the random numbers
+    rng: StdRng, // the one and only source of random numbers
+    playing: BTreeMap<String, (Option<String>, Box<dyn MiniGame>)>, // each player's mini game, and who started it
+    scheduled: Vec<(u32, String, Action)>, // actions to send later: turns to go, object name, action
+    points: i32,                           // awarded by objects, not yet added to the score
+    factories: BTreeMap<String, Factory>,  // prototypes for objects made during the game, by kind
+    spawned: u32,                          // objects made so far, for unique names
+    published: VecDeque<Event>,            // events waiting to be delivered
+    publishing: bool,                      // delivering events now
+}
+
+impl Default for GameAtlas {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
 }
 
 impl GameAtlas {
     pub fn new(here: String) -> Self {
+        let seed = rand::random::<u64>();
         Self {
-            here,
-            atlas: HashMap::new(),
+            player: PLAYER.to_string(),
+            players: BTreeMap::from([(PLAYER.to_string(), here)]),
+            news: Vec::new(),
+            atlas: BTreeMap::new(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            playing: BTreeMap::new(),
+            scheduled: Vec::new(),
+            points: 0,
+            factories: BTreeMap::new(),
+            spawned: 0,
+            published: VecDeque::new(),
+            publishing: false,
         }
     }
 
+    /// Get the seed for the random numbers.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restart the random numbers from the given seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Get the random number generator.
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
     /// Get the current location.
     pub fn here(&self) -> String {
-        self.here.clone()
+        self.players
+            .get(&self.player)
+            .cloned()
+            .unwrap_or(NOWHERE.to_string())
     }
 
     /// Set the current location.
     pub fn set_here(&mut self, here: String) {
-        self.here = here;
+        let from = self.here();
+        if from != here {
+            self.announce(&from, format!("{} leaves.", self.player));
+        }
+        self.players.insert(self.player.clone(), here.clone());
+        if from != here {
+            self.announce(&here, format!("{} arrives.", self.player));
+        }
+    }
+
+    /// The player taking their turn.
+    pub fn player(&self) -> String {
+        self.player.clone()
+    }
+
+    /// Let another player take their turn.
+    pub fn set_player(&mut self, name: String) {
+        self.player = name;
+    }
+
+    /// Add a player to the world, and make it their turn.
+    pub fn add_player(&mut self, name: String, room: String) {
+        self.player = name.clone();
+        self.players.insert(name.clone(), room.clone());
+        self.announce(&room, format!("{} appears out of nowhere.", name));
+    }
+
+    /// Remove a player from the world. Whatever they carried is left where they stood.
+    pub fn remove_player(&mut self, name: String) {
+        self.player = name.clone();
+        let here = self.here();
+        let inventory = self.inventory();
+        for rc in self.atlas.values() {
+            let mut o = rc.borrow_mut();
+            if o.loc() == inventory {
+                o.set_loc(here.clone());
+            }
+        }
+        self.announce(&here, format!("{} vanishes.", name));
+        self.players.remove(&name);
+        self.playing.remove(&name);
+    }
+
+    /// Get the other players in the same place as the current player.
+    pub fn players_here(&self) -> Vec<String> {
+        let here = self.here();
+        self.players
+            .iter()
+            .filter(|(name, room)| **name != self.player && **room == here)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Tell the other players in the room what the current player is up to.
+    fn announce(&mut self, room: &str, text: String) {
+        for (name, _) in self.players.iter().filter(|(_, r)| r.as_str() == room) {
+            if *name != self.player {
+                self.news.push((name.clone(), text.clone()));
+            }
+        }
+    }
+
+    /// Take the messages for other players: who to tell, and what to say.
+    pub fn take_news(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.news)
+    }
+
+    /// Get the location of the current player's inventory.
+    fn inventory(&self) -> String {
+        if self.player == PLAYER {
+            INVENTORY.to_string()
+        } else {
+            format!("{}:{}", INVENTORY, self.player)
+        }
     }
 
     /// Add a list of objects to the game.
@@ -51,7 +186,7 @@ impl GameAtlas {
     /// If the object already exists, it will not be added.
     pub fn add(&mut self, object: Box<dyn GameObject>) {
         if self.atlas.contains_key(&object.name()) {
-            println!("cannot add duplicate object: '{}'", object.name());
+            outln!("cannot add duplicate object: '{}'", object.name());
             return;
         }
         self.atlas.insert(object.name(), RefCell::new(object));
@@ -60,15 +195,18 @@ impl GameAtlas {
     /// Get an immutable reference to the object. (Used primarily for testing.)
     #[allow(dead_code)]
     pub fn get(&self, name: String) -> Option<Ref<'_, Box<dyn GameObject>>> {
-        self.atlas.get(&name).map_or(None, |o| Some(o.borrow()))
+        self.atlas.get(&name).map(|o| o.borrow())
     }
 
     /// Get a mutable reference to the object. (Used primarily for testing.)
     #[allow(dead_code)]
     pub fn get_mut(&mut self, name: String) -> Option<RefMut<'_, Box<dyn GameObject>>> {
-        self.atlas
-            .get_mut(&name)
-            .map_or(None, |o| Some(o.borrow_mut()))
+        self.atlas.get_mut(&name).map(|o| o.borrow_mut())
+    }
+
+    /// Get the location of the object, if it exists.
+    pub fn loc_of(&self, name: String) -> Option<String> {
+        self.atlas.get(&name).map(|o| o.borrow().loc())
     }
 
     /// Set the location of the object.
@@ -81,12 +219,13 @@ impl GameAtlas {
     }
 
     /// Get all objects in the given location, but not the location itself.
+    /// Doors are in every room they join.
     pub fn get_locals(&self, here: String) -> Vec<Ref<'_, Box<dyn GameObject>>> {
         self.atlas
-            .iter()
-            .filter_map(|(_k, v)| {
+            .values()
+            .filter_map(|v| {
                 let v = v.borrow();
-                if v.loc() == here {
+                if v.loc() == here || v.sides().contains(&here) {
                     Some(v)
                 } else {
                     None
@@ -103,10 +242,10 @@ impl GameAtlas {
     /// Get all objects in the inventory.
     pub fn get_inventory(&self) -> Vec<Ref<'_, Box<dyn GameObject>>> {
         self.atlas
-            .iter()
-            .filter_map(|(_k, v)| {
+            .values()
+            .filter_map(|v| {
                 let v = v.borrow();
-                if v.loc().as_str() == INVENTORY {
+                if v.loc() == self.inventory() {
                     Some(v)
                 } else {
                     None
@@ -116,52 +255,312 @@ impl GameAtlas {
     }
 
     /// Objects, inventory, and here for the given location.
-    pub fn _get_context_for(&self, here: String) -> GameContext {
+    pub fn _get_context_for(&self, here: String) -> GameContext<'_> {
         GameContext::new(
             here.clone(),
             self.get_locals(here.clone()),
             self.get_inventory(),
+            self.rooms(),
         )
     }
 
     /// Shortcut for get_context(self.here())
-    pub fn get_context(&self) -> GameContext {
-        GameContext::new(self.here(), self.get_locals_here(), self.get_inventory())
+    pub fn get_context(&self) -> GameContext<'_> {
+        GameContext::new(
+            self.here(),
+            self.get_locals_here(),
+            self.get_inventory(),
+            self.rooms(),
+        )
     }
 
     /// Move the object to the inventory.
     pub fn move_inventory(&mut self, object_name: String) -> bool {
-        if let Some(rc) = self.atlas.get(&object_name) {
-            let mut o = rc.borrow_mut();
-            println!("** {} moves from {} to inventory", o.name(), o.loc());
-            o.set_loc(INVENTORY.to_string());
-            return true;
+        let taken = match self.atlas.get(&object_name) {
+            Some(rc) => {
+                let mut o = rc.borrow_mut();
+                outln!("** {} moves from {} to inventory", o.name(), o.loc());
+                o.set_loc(self.inventory());
+                true
+            }
+            None => false,
+        };
+        if taken {
+            self.announce(
+                &self.here(),
+                format!("{} takes the {}.", self.player, object_name),
+            );
+            self.publish(Event::Taken(self.player.clone(), object_name));
         }
-        false
+        taken
     }
 
     /// Move the object to the current location.
     pub fn move_local(&mut self, object_name: String) -> bool {
-        if let Some(rc) = self.atlas.get(&object_name) {
-            let mut o = rc.borrow_mut();
-            println!("** {} appears in the {}", o.name(), self.here());
-            o.set_loc(self.here());
-            return true;
+        let dropped = match self.atlas.get(&object_name) {
+            Some(rc) => {
+                let mut o = rc.borrow_mut();
+                outln!("** {} appears in the {}", o.name(), self.here());
+                let dropped = o.loc() == self.inventory();
+                o.set_loc(self.here());
+                Some(dropped)
+            }
+            None => None,
+        };
+        if dropped == Some(true) {
+            self.announce(
+                &self.here(),
+                format!("{} drops the {}.", self.player, object_name),
+            );
+            self.publish(Event::Dropped(self.player.clone(), object_name));
+        }
+        dropped.is_some()
+    }
+
+    /// Move the carried object in (or on) the container.
+    pub fn move_into(&mut self, object_name: String, container: String) -> bool {
+        let dropped = match self.atlas.get(&object_name) {
+            Some(rc) => {
+                let mut o = rc.borrow_mut();
+                outln!("** {} moves from {} to {}", o.name(), o.loc(), container);
+                let dropped = o.loc() == self.inventory();
+                o.set_loc(container.clone());
+                Some(dropped)
+            }
+            None => None,
+        };
+        if dropped == Some(true) {
+            self.announce(
+                &self.here(),
+                format!("{} puts the {} away.", self.player, object_name),
+            );
+            self.publish(Event::Dropped(self.player.clone(), object_name));
+        }
+        dropped.is_some()
+    }
+
+    /// Get the names of the objects in (or on) the container.
+    pub fn contents(&self, container: &str) -> Vec<String> {
+        self.get_locals(container.to_string())
+            .iter()
+            .map(|o| o.name())
+            .collect()
+    }
+
+    /// Is the object inside the other, or inside something that's inside it, and so on?
+    pub fn is_inside(&self, name: &str, outer: &str) -> bool {
+        let mut name = name.to_string();
+        // Each step goes one container out, so there can't be more steps than objects.
+        for _ in 0..self.atlas.len() {
+            match self.loc_of(name) {
+                Some(loc) if loc == outer => return true,
+                Some(loc) => name = loc,
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// Can the player get at the object? It's the room, it's here, it's carried,
+    /// or it's in an open container they can get at.
+    pub fn is_reachable(&self, name: &str) -> bool {
+        let (here, inventory) = (self.here(), self.inventory());
+        let mut name = name.to_string();
+        for _ in 0..=self.atlas.len() {
+            if name == here
+                || self
+                    .atlas
+                    .get(&name)
+                    .is_some_and(|o| o.borrow().sides().contains(&here))
+            {
+                return true;
+            }
+            let loc = match self.loc_of(name) {
+                Some(loc) => loc,
+                None => return false,
+            };
+            if loc == here || loc == inventory {
+                return true;
+            }
+            match self.atlas.get(&loc).and_then(|o| o.borrow().container()) {
+                Some(container) if container.open => name = loc,
+                _ => return false,
+            }
         }
         false
     }
 
+    /// The closed container, within reach, that keeps the object out of reach. If there is one.
+    pub fn closed_container(&self, name: &str) -> Option<String> {
+        let mut name = name.to_string();
+        for _ in 0..=self.atlas.len() {
+            let loc = self.loc_of(name)?;
+            match self.atlas.get(&loc).and_then(|o| o.borrow().container()) {
+                Some(container) if !container.open => {
+                    return Some(loc).filter(|loc| self.is_reachable(loc));
+                }
+                Some(_) => name = loc,
+                None => return None,
+            }
+        }
+        None
+    }
+
     /// Remove the object from the game. (Move it to nowhere.)
-    pub fn _remove_object(&mut self, object_name: String) -> bool {
+    pub fn remove_object(&mut self, object_name: String) -> bool {
         if let Some(rc) = self.atlas.get(&object_name) {
             let mut o = rc.borrow_mut();
-            println!("** {} disappears from the {}", o.name(), o.loc());
+            outln!("** {} disappears from the {}", o.name(), o.loc());
             o.set_loc(NOWHERE.to_string());
             return true;
         }
         false
     }
 
+    /// Register a prototype, so objects of this kind can be made during the game.
+    pub fn register(&mut self, kind: &str, factory: Factory) {
+        self.factories.insert(kind.to_string(), factory);
+    }
+
+    /// Make a new object of a registered kind, and put it in the location.
+    /// Every object made gets a unique name, e.g. "half#2". Returns the name, or None if the kind is unknown.
+    /// ! Objects made this way should answer to their kind too. (See GameObject::aliases.)
+    pub fn spawn(&mut self, kind: &str, location: Location) -> Option<String> {
+        let factory = self.factories.get(kind)?;
+        let name = loop {
+            self.spawned += 1;
+            let name = format!("{}#{}", kind, self.spawned);
+            if !self.atlas.contains_key(&name) {
+                break name;
+            }
+        };
+        let mut object = factory(name.clone());
+        let loc = match location {
+            Location::Local => self.here(),
+            Location::Inventory => self.inventory(),
+            Location::To(loc) => loc,
+        };
+        object.set_loc(loc);
+        self.add(object);
+        Some(name)
+    }
+
+    /// Take the object out of the game for good.
+    pub fn destroy_object(&mut self, object_name: String) -> bool {
+        self.atlas.remove(&object_name).is_some()
+    }
+
+    /// Is the flag set on the object?
+    pub fn flag(&self, name: String, flag: &str) -> bool {
+        self.atlas.get(&name).is_some_and(|o| o.borrow().flag(flag))
+    }
+
+    /// Take the points awarded by objects since the last time.
+    pub fn take_points(&mut self) -> i32 {
+        std::mem::take(&mut self.points)
+    }
+
+    /// Count down the scheduled events by one turn, and send the actions that are due.
+    /// Returns true if any of them were handled.
+    pub fn tick_events(&mut self) -> Handled {
+        let mut due = Vec::new();
+        self.scheduled.retain_mut(|(turns, name, action)| {
+            *turns = turns.saturating_sub(1);
+            if *turns == 0 {
+                due.push((name.clone(), action.clone()));
+            }
+            *turns > 0
+        });
+
+        let mut handled = false;
+        for (name, action) in due {
+            handled |= self.invoke(action, name);
+        }
+        handled
+    }
+
+    /// Get the declared exits of every room, sorted by room name.
+    pub fn get_exits(&self) -> Vec<(String, Vec<(Direction, String)>)> {
+        let mut exits: BTreeMap<String, Vec<(Direction, String)>> = BTreeMap::new();
+        for o in self.atlas.values() {
+            let o = o.borrow();
+            // Rooms have their own exits. Gates and doors add to the exits of the room they're in.
+            let room = if o.loc() == NOWHERE {
+                o.name()
+            } else {
+                o.loc()
+            };
+            exits.entry(room).or_default().extend(o.exits());
+        }
+        exits.into_iter().filter(|(_, e)| !e.is_empty()).collect()
+    }
+
+    /// Names of every room, that is, everywhere with a way out.
+    pub fn rooms(&self) -> Vec<String> {
+        self.get_exits().into_iter().map(|(room, _)| room).collect()
+    }
+
+    /// Find the door between two rooms, if there is one.
+    pub fn door_between(&self, from: &str, to: &str) -> Option<String> {
+        self.atlas
+            .values()
+            .map(|o| o.borrow())
+            .find(|o| {
+                let sides = o.sides();
+                sides.iter().any(|s| s == from) && sides.iter().any(|s| s == to)
+            })
+            .map(|o| o.name())
+    }
+
+    /// Get the weapon properties of the object, if any.
+    pub fn get_weapon(&self, name: String) -> Option<Weapon> {
+        self.atlas.get(&name).and_then(|o| o.borrow().weapon())
+    }
+
+    /// Get the health of the object, if it is a creature.
+    pub fn get_health(&self, name: String) -> Option<Health> {
+        self.atlas.get(&name).and_then(|o| o.borrow().health())
+    }
+
+    /// Update the health of the creature.
+    pub fn set_health(&mut self, name: String, health: Health) -> bool {
+        if let Some(o) = self.atlas.get(&name) {
+            o.borrow_mut().set_health(health);
+            return true;
+        }
+        false
+    }
+
+    /// Get the lock properties of the object, if it opens and closes.
+    pub fn get_lockable(&self, name: String) -> Option<Lockable> {
+        self.atlas.get(&name).and_then(|o| o.borrow().lockable())
+    }
+
+    /// Open, close, lock or unlock the object.
+    pub fn set_lockable(&mut self, name: String, lockable: Lockable) -> bool {
+        if let Some(o) = self.atlas.get(&name) {
+            o.borrow_mut().set_lockable(lockable);
+            return true;
+        }
+        false
+    }
+
+    /// Advance the health of every creature by one turn, so wounds heal.
+    /// Returns the names of creatures that regained consciousness.
+    pub fn heal_all(&mut self) -> Vec<String> {
+        let mut woke = Vec::new();
+        for rc in self.atlas.values() {
+            let mut o = rc.borrow_mut();
+            if let Some(mut health) = o.health() {
+                if health.tick() {
+                    woke.push(o.name());
+                }
+                o.set_health(health);
+            }
+        }
+        woke
+    }
+
     /// Replace the old object with a new object in the same location.
     /// Good for replacing a key with a loaf of bread, for example.
     /// NOTE: Method required two mutable borrows of the atlas, hence Rc.
@@ -170,13 +569,13 @@ impl GameAtlas {
             if let Some(rc2) = self.atlas.get(&new_name) {
                 let mut o1 = rc1.borrow_mut();
                 let mut o2 = rc2.borrow_mut();
-                println!("** {} is replaced by the {}", o1.name(), o2.name());
+                outln!("** {} is replaced by the {}", o1.name(), o2.name());
                 o2.set_loc(o1.loc());
                 o1.set_loc(NOWHERE.to_string());
                 return true;
             }
         }
-        return false;
+        false
     }
 
     /// Invoke action on all objects. Returns true if the action was handled by any.
@@ -187,7 +586,9 @@ impl GameAtlas {
                 Some(name) => self.invoke(action.clone(), name.clone()),
                 None => false,
             })
-            .fold(false, |acc, x| acc || x) // Returns true if any are true.
+            .filter(|x| *x)
+            .count()
+            > 0 // Invokes every object, then returns true if any are true.
     }
 
     /// Shortcut for Describe all objects in list.
@@ -208,13 +609,162 @@ impl GameAtlas {
             .unwrap_or(false)
     }
 
+    /// The objects that can intercept an action: the room, then what the player carries.
+    fn interceptors(&self) -> Vec<String> {
+        let mut names = vec![self.here()];
+        names.extend(self.get_inventory().iter().map(|o| o.name()));
+        names
+    }
+
+    /// Give the room and what the player carries a chance to stop the action, or to replace it.
+    /// Returns Unhandled to let it through, Instead with the action to play in its place, or Handled if it was stopped.
+    pub fn before(&mut self, action: &Action) -> Notify {
+        for name in self.interceptors() {
+            let notification = match self.atlas.get(&name) {
+                Some(rc) => rc.borrow_mut().before(action),
+                None => continue,
+            };
+            match notification {
+                Notify::Unhandled => continue,
+                Notify::Instead(action) => return Notify::Instead(action),
+                notification => {
+                    self.notify(name, notification);
+                    return Notify::Handled;
+                }
+            }
+        }
+        Notify::Unhandled
+    }
+
+    /// Let the room and what the player carries react to an action that was handled.
+    pub fn after(&mut self, action: &Action) {
+        for name in self.interceptors() {
+            let notification = match self.atlas.get(&name) {
+                Some(rc) => rc.borrow_mut().after(action),
+                None => continue,
+            };
+            self.notify(name, notification);
+        }
+    }
+
     /// Shortcut to invoke the action on the current location only.
     pub fn invoke_here(&mut self, action: Action) -> Handled {
         self.invoke(action, self.here())
     }
 
+    /// Start a mini game for the current player. It takes all their input until it's over, then `owner` hears how it went.
+    pub fn start_mini(&mut self, owner: Option<String>, mut game: Box<dyn MiniGame>) {
+        game.start(&mut self.rng);
+        self.playing.insert(self.player.clone(), (owner, game));
+    }
+
+    /// Is the current player in the middle of a mini game?
+    pub fn is_playing(&self) -> bool {
+        self.playing.contains_key(&self.player)
+    }
+
+    /// Pass a line of input to the current player's mini game. Returns the outcome when it's over.
+    pub fn play_mini(&mut self, line: &str) -> Option<Outcome> {
+        let (_, game) = self.playing.get_mut(&self.player)?;
+        let outcome = game.input(line, &mut self.rng)?;
+
+        let (owner, game) = self.playing.remove(&self.player)?;
+        outln!("\n[Back to the adventure.]");
+        if let Some(owner) = owner {
+            self.invoke(Action::Played(game.name(), outcome), owner);
+        }
+        Some(outcome)
+    }
+
+    /// Give every object near the player (here, in the room, or carried) a turn to do something on its own.
+    /// Returns true if any of them did.
+    pub fn tick_here(&mut self) -> Handled {
+        self.tick_near(&mut BTreeSet::new())
+    }
+
+    /// Like tick_here, but only for what isn't in `ticked` yet. Everything that gets its turn is added to it.
+    /// In a shared world, nothing acts twice in a turn, however many players are near it.
+    pub fn tick_near(&mut self, ticked: &mut BTreeSet<String>) -> Handled {
+        let here = self.here();
+        let names: Vec<String> = self
+            .atlas
+            .values()
+            .map(|o| o.borrow())
+            .filter(|o| o.name() == here || o.loc() == here || o.loc() == self.inventory())
+            .map(|o| o.name())
+            .filter(|name| ticked.insert(name.clone()))
+            .collect();
+
+        let mut handled = false;
+        for name in names {
+            let notification = match self.atlas.get(&name) {
+                Some(rc) => rc.borrow_mut().tick(&mut self.rng),
+                None => continue,
+            };
+            handled |= self.notify(name, notification);
+        }
+        handled
+    }
+    /// Tell every object that observes the event, in name order. Returns true if any of them reacted.
+    /// ! Events published while reacting are queued, and delivered in turn once these are done.
+    pub fn publish(&mut self, event: Event) -> Handled {
+        self.published.push_back(event);
+        if self.publishing {
+            return false;
+        }
+
+        self.publishing = true;
+        let mut handled = false;
+        while let Some(event) = self.published.pop_front() {
+            let names: Vec<String> = self
+                .atlas
+                .values()
+                .map(|o| o.borrow())
+                .filter(|o| o.observes(&event))
+                .map(|o| o.name())
+                .collect();
+            for name in names {
+                let notification = match self.atlas.get(&name) {
+                    Some(rc) => rc.borrow_mut().observe(event.clone()),
+                    None => continue,
+                };
+                handled |= self.notify(name, notification);
+            }
+        }
+        self.publishing = false;
+        handled
+    }
+
+    /// Is the player carrying the object?
+    pub fn is_carried(&self, name: &str) -> bool {
+        self.loc_of(name.to_string()) == Some(self.inventory())
+    }
+
+    /// Find an object by name, or by another name it answers to. Returns its real name.
+    /// When several answer to the same name, the one here wins, then the one carried.
+    pub fn resolve(&self, name: String) -> Option<String> {
+        if self.atlas.contains_key(&name) {
+            return Some(name);
+        }
+        let (here, inventory) = (self.here(), self.inventory());
+        self.atlas
+            .values()
+            .map(|o| o.borrow())
+            .filter(|o| o.aliases().contains(&name))
+            .min_by_key(|o| match o.loc() {
+                loc if loc == here => 0,
+                loc if loc == inventory => 1,
+                _ => 2,
+            })
+            .map(|o| o.name())
+    }
+
     /// Invoke a specific action on the specified object. Returns true if the action was handled.
     pub fn invoke(&mut self, action: Action, object_name: String) -> Handled {
+        let object_name = match self.resolve(object_name) {
+            Some(name) => name,
+            None => return false,
+        };
         if let Some(rc) = self.atlas.get(&object_name) {
             let notification: Notify = {
                 let mut o = rc.borrow_mut();
@@ -224,28 +774,228 @@ impl GameAtlas {
                     Notify::Unhandled
                 }
             };
-            match notification {
-                Notify::Handled => true,
-                Notify::Unhandled => false,
+            self.notify(object_name, notification)
+        } else {
+            false
+        }
+    }
 
-                Notify::Set(location) => match location {
-                    Location::To(name) => {
-                        self.set_here(name);
-                        true
-                    }
-                    _ => false,
-                },
+    /// Carry out the request of the named object. Returns true if it was handled.
+    pub(crate) fn notify(&mut self, name: String, notification: Notify) -> Handled {
+        match notification {
+            Notify::Handled => true,
+            Notify::Unhandled => false,
+
+            Notify::Set(location) => match location {
+                Location::To(name) => {
+                    self.set_here(name);
+                    true
+                }
+                _ => false,
+            },
+
+            Notify::Move(object_name, location) => match location {
+                Location::Local => self.move_local(object_name),
+                Location::Inventory => self.move_inventory(object_name),
+                Location::To(name) => self.set_loc(object_name, name),
+            },
 
-                Notify::Move(object_name, location) => match location {
-                    Location::Local => self.move_local(object_name),
-                    Location::Inventory => self.move_inventory(object_name),
-                    Location::To(name) => self.set_loc(object_name, name),
-                },
+            Notify::Replace(old_obj, new_obj) => self.replace_object(old_obj, new_obj),
 
-                Notify::Replace(old_obj, new_obj) => self.replace_object(old_obj, new_obj),
+            Notify::Play(game) => {
+                self.start_mini(Some(name), game);
+                true
+            }
+
+            Notify::Effects(effects) => self.apply(name, effects),
+
+            // Only the game can play another action, and only before one. (See GameAtlas::before.)
+            Notify::Instead(_) => false,
+        }
+    }
+
+    /// Could all of these effects be applied, one after the other?
+    /// Every object they name, and every object something is moved to, must exist by then,
+    /// and nothing can be created twice.
+    /// ! Only names are checked, not whether each move makes sense.
+    fn is_possible(&self, effects: &[Effect]) -> bool {
+        let mut names: BTreeSet<String> = self.atlas.keys().cloned().collect();
+        // Things can go in any object, or in a player's hands.
+        let is_place = |names: &BTreeSet<String>, target: &String| {
+            names.contains(target) || target.starts_with(INVENTORY)
+        };
+        effects.iter().all(|effect| match effect {
+            Effect::Create(object) => names.insert(object.name()),
+            Effect::Destroy(name) => names.remove(name),
+            Effect::Move(name, Location::To(target)) => {
+                names.contains(name) && is_place(&names, target)
+            }
+            Effect::Move(name, _)
+            | Effect::SetRoom(name)
+            | Effect::SetFlag(name, _, _)
+            | Effect::Schedule(_, name, _) => names.contains(name),
+            Effect::Replace(old, new) => names.contains(old) && names.contains(new),
+            Effect::Spawn(kind, Location::To(target)) => {
+                self.factories.contains_key(kind) && is_place(&names, target)
+            }
+            Effect::Spawn(kind, _) => self.factories.contains_key(kind),
+            Effect::Score(_) | Effect::Print(_) => true,
+        })
+    }
+
+    /// Apply the effects requested by the named object, in order.
+    /// ! Either all of them are applied, or none of them are.
+    fn apply(&mut self, name: String, effects: Vec<Effect>) -> Handled {
+        if !self.is_possible(&effects) {
+            return false;
+        }
+        for effect in effects {
+            match effect {
+                Effect::Move(object_name, location) => {
+                    self.notify(name.clone(), Notify::Move(object_name, location));
+                }
+                Effect::Replace(old, new) => {
+                    self.replace_object(old, new);
+                }
+                Effect::Create(object) => {
+                    self.add(object);
+                }
+                Effect::Spawn(kind, location) => {
+                    self.spawn(&kind, location);
+                }
+                Effect::Destroy(object_name) => {
+                    self.destroy_object(object_name);
+                }
+                Effect::SetRoom(room) => self.set_here(room),
+                Effect::SetFlag(object_name, flag, value) => {
+                    if let Some(mut o) = self.get_mut(object_name) {
+                        o.set_flag(&flag, value);
+                    }
+                }
+                Effect::Score(points) => self.points += points,
+                Effect::Schedule(turns, object_name, action) => {
+                    self.scheduled.push((turns, object_name, action))
+                }
+                Effect::Print(text) => outln!("{}", text),
             }
-        } else {
-            false
         }
+        true
+    }
+}
+
+#[cfg(all(test, feature = "world"))]
+mod tests {
+    use super::*;
+    use crate::game::output;
+    use crate::world::forest::{self, FOREST, KEY, LEAVES};
+    use crate::world::kitchen::{self, HALF};
+
+    fn setup() -> GameAtlas {
+        let mut vec = Vec::new() as Vec<Box<dyn GameObject>>;
+        forest::create(&mut vec);
+        let mut atlas = GameAtlas::new(FOREST.to_string());
+        atlas.add_all(vec);
+        atlas
+    }
+
+    #[test]
+    fn test_effects_all_or_nothing() {
+        let mut atlas = setup();
+        let effects = vec![
+            Effect::Move(KEY.to_string(), Location::Local),
+            Effect::Destroy(String::from("unicorn")),
+        ];
+        let (handled, text) =
+            output::capture(|| atlas.notify(LEAVES.to_string(), Notify::Effects(effects)));
+        assert!(!handled);
+        assert!(text.is_empty(), "the player hears nothing of it");
+        assert_eq!(atlas.loc_of(KEY.to_string()), Some(LEAVES.to_string()));
+
+        let effects = vec![
+            Effect::Move(KEY.to_string(), Location::Local),
+            Effect::Score(3),
+            Effect::Move(KEY.to_string(), Location::To(String::from("unicorn"))),
+        ];
+        assert!(!atlas.notify(LEAVES.to_string(), Notify::Effects(effects)));
+        assert_eq!(atlas.loc_of(KEY.to_string()), Some(LEAVES.to_string()));
+
+        let effects = vec![
+            Effect::Move(KEY.to_string(), Location::Local),
+            Effect::Score(3),
+            Effect::Destroy(LEAVES.to_string()),
+        ];
+        assert!(atlas.notify(LEAVES.to_string(), Notify::Effects(effects)));
+        assert_eq!(atlas.loc_of(KEY.to_string()), Some(FOREST.to_string()));
+        assert!(atlas.get(LEAVES.to_string()).is_none());
+        assert_eq!(atlas.take_points(), 3);
+        assert_eq!(atlas.take_points(), 0);
+    }
+
+    #[test]
+    fn test_scheduled_event() {
+        let mut atlas = setup();
+        let kick = Action::Attack(LEAVES.to_string(), None);
+        let effects = vec![Effect::Schedule(2, LEAVES.to_string(), kick)];
+        atlas.notify(FOREST.to_string(), Notify::Effects(effects));
+
+        assert!(!atlas.tick_events());
+        assert_eq!(atlas.loc_of(KEY.to_string()), Some(LEAVES.to_string()));
+        assert!(atlas.tick_events());
+        assert_eq!(atlas.loc_of(KEY.to_string()), Some(FOREST.to_string()));
+        assert!(!atlas.tick_events());
+    }
+
+    #[test]
+    fn test_publish() {
+        let mut atlas = setup();
+        let left = Event::Left(PLAYER.to_string(), FOREST.to_string());
+        let (handled, text) = output::capture(|| atlas.publish(left));
+        assert!(handled, "the raccoon watches you leave");
+        assert!(text.contains("raccoon"));
+
+        let arrived = Event::Arrived(PLAYER.to_string(), FOREST.to_string());
+        let (handled, text) = output::capture(|| atlas.publish(arrived));
+        assert!(!handled);
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn test_spawn_and_destroy() {
+        let mut atlas = setup();
+        assert_eq!(atlas.spawn(HALF, Location::Local), None);
+        kitchen::register(&mut atlas);
+
+        let first = atlas.spawn(HALF, Location::Inventory).unwrap();
+        let second = atlas.spawn(HALF, Location::Local).unwrap();
+        assert_eq!((first.as_str(), second.as_str()), ("half#1", "half#2"));
+        assert_eq!(atlas.loc_of(second.clone()), Some(FOREST.to_string()));
+        // Both answer to "half", but the one here comes first.
+        assert_eq!(atlas.resolve(HALF.to_string()), Some(second.clone()));
+
+        assert!(atlas.destroy_object(second));
+        assert_eq!(atlas.resolve(HALF.to_string()), Some(first));
+        // Names are never used twice.
+        let effects = vec![Effect::Spawn(HALF.to_string(), Location::Local)];
+        assert!(atlas.notify(FOREST.to_string(), Notify::Effects(effects)));
+        assert!(atlas.get(String::from("half#3")).is_some());
+        assert_eq!(kind_of("half#3"), HALF);
+        assert_eq!(kind_of(FOREST), FOREST);
+
+        // Nothing is made anywhere that isn't, and nothing else happens either.
+        let effects = vec![
+            Effect::Score(1),
+            Effect::Spawn(HALF.to_string(), Location::To(String::from("unicorn"))),
+        ];
+        let (handled, text) =
+            output::capture(|| atlas.notify(FOREST.to_string(), Notify::Effects(effects)));
+        assert!(!handled);
+        assert!(text.is_empty());
+        assert!(atlas.get(String::from("half#4")).is_none());
+        assert_eq!(atlas.take_points(), 0);
+        let effects = vec![Effect::Spawn(
+            HALF.to_string(),
+            Location::To(INVENTORY.to_string()),
+        )];
+        assert!(atlas.notify(FOREST.to_string(), Notify::Effects(effects)));
     }
 }
@@ -0,0 +1,186 @@
+use rand::Rng;
+
+/// Weapon properties. Any object that can be wielded (or any creature with teeth and claws) has one.
+/// Damage is rolled uniformly between `min` and `max` on a hit, so `min` can't be more than `max`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weapon {
+    pub min: i32,      // least damage on a hit
+    pub max: i32,      // most damage on a hit
+    pub accuracy: u32, // chance to hit, in percent
+}
+
+impl Weapon {
+    pub const fn new(min: i32, max: i32, accuracy: u32) -> Self {
+        assert!(min <= max, "min damage is more than max");
+        assert!(accuracy <= 100, "accuracy is more than 100 percent");
+        Self { min, max, accuracy }
+    }
+}
+
+/// Used when the attacker isn't holding anything.
+pub static FISTS: Weapon = Weapon::new(1, 2, 70);
+
+/// Hit points for the player and for any creature that can be fought.
+/// Wounds heal by one point every `regen` turns, unless the creature is unconscious.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Health {
+    hp: i32,      // current hit points
+    max: i32,     // hit points when fully healed
+    regen: u32,   // turns needed to heal one hit point
+    clock: u32,   // turns since last healed
+    stunned: u32, // turns left until conscious again
+}
+
+/// The result of a single blow.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Missed,
+    Wounded(i32),    // damage dealt
+    KnockedOut(i32), // damage dealt, target is unconscious
+    Killed(i32),     // damage dealt, target is dead
+}
+
+/// Turns spent unconscious after a knockout.
+pub static KNOCKOUT_TURNS: u32 = 3;
+
+impl Health {
+    pub fn new(max: i32, regen: u32) -> Self {
+        Self {
+            hp: max,
+            max,
+            regen,
+            clock: 0,
+            stunned: 0,
+        }
+    }
+
+    pub fn hp(&self) -> i32 {
+        self.hp
+    }
+
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.hp > 0
+    }
+
+    pub fn is_conscious(&self) -> bool {
+        self.is_alive() && self.stunned == 0
+    }
+
+    pub fn is_wounded(&self) -> bool {
+        self.hp < self.max
+    }
+
+    /// Apply damage. A blow that drops the target to a quarter of its health (or less) knocks it out.
+    pub fn wound(&mut self, damage: i32) -> Outcome {
+        let was_standing = self.hp * 4 > self.max;
+        self.hp -= damage;
+        self.clock = 0;
+        if self.hp <= 0 {
+            self.hp = 0;
+            Outcome::Killed(damage)
+        } else if was_standing && self.hp * 4 <= self.max {
+            self.stunned = KNOCKOUT_TURNS;
+            Outcome::KnockedOut(damage)
+        } else {
+            Outcome::Wounded(damage)
+        }
+    }
+
+    /// Advance one turn. Returns true if the creature just regained consciousness.
+    pub fn tick(&mut self) -> bool {
+        if !self.is_alive() {
+            return false;
+        }
+        if self.stunned > 0 {
+            self.stunned -= 1;
+            return self.stunned == 0;
+        }
+        if self.is_wounded() {
+            self.clock += 1;
+            if self.clock >= self.regen {
+                self.clock = 0;
+                self.hp += 1;
+            }
+        }
+        false
+    }
+}
+
+/// Swing the weapon at the target. Unconscious targets are always hit.
+pub fn strike<R: Rng>(rng: &mut R, weapon: &Weapon, target: &mut Health) -> Outcome {
+    if !target.is_alive() {
+        return Outcome::Missed;
+    }
+    if target.is_conscious() && rng.gen_range(0..100) >= weapon.accuracy {
+        return Outcome::Missed;
+    }
+    let damage = rng.gen_range(weapon.min..=weapon.max);
+    target.wound(damage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wound_and_heal() {
+        let mut health = Health::new(10, 2);
+        assert_eq!(health.wound(3), Outcome::Wounded(3));
+        assert_eq!(health.hp(), 7);
+
+        health.tick();
+        assert_eq!(health.hp(), 7);
+        health.tick();
+        assert_eq!(health.hp(), 8);
+    }
+
+    #[test]
+    fn test_knockout() {
+        let mut health = Health::new(8, 1);
+        assert_eq!(health.wound(6), Outcome::KnockedOut(6));
+        assert!(health.is_alive());
+        assert!(!health.is_conscious());
+
+        // No healing while unconscious.
+        for _ in 1..KNOCKOUT_TURNS {
+            assert!(!health.tick());
+        }
+        assert!(health.tick());
+        assert!(health.is_conscious());
+        assert_eq!(health.hp(), 2);
+    }
+
+    #[test]
+    fn test_killed() {
+        let mut health = Health::new(4, 1);
+        assert_eq!(health.wound(5), Outcome::Killed(5));
+        assert!(!health.is_alive());
+        assert!(!health.tick());
+    }
+
+    #[test]
+    fn test_strike_sure_hit() {
+        let mut rng = rand::thread_rng();
+        let mut health = Health::new(10, 1);
+        let sword = Weapon::new(2, 2, 100);
+        assert_eq!(strike(&mut rng, &sword, &mut health), Outcome::Wounded(2));
+    }
+
+    #[test]
+    fn test_strike_sure_miss() {
+        let mut rng = rand::thread_rng();
+        let mut health = Health::new(10, 1);
+        let noodle = Weapon::new(1, 1, 0);
+        assert_eq!(strike(&mut rng, &noodle, &mut health), Outcome::Missed);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_weapon_backwards() {
+        Weapon::new(3, 1, 50);
+    }
+}
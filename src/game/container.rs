@@ -0,0 +1,35 @@
+/// Container properties. Any object that things can be put in (or on) has one.
+/// Surfaces, like a counter, are never closed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Container {
+    pub capacity: usize, // objects it can hold at once
+    pub open: bool,      // can things be put in, or taken out?
+    pub surface: bool,   // things go on it, not in it
+}
+
+impl Container {
+    pub const fn new(capacity: usize, open: bool) -> Self {
+        Self {
+            capacity,
+            open,
+            surface: false,
+        }
+    }
+
+    pub const fn surface(capacity: usize) -> Self {
+        Self {
+            capacity,
+            open: true,
+            surface: true,
+        }
+    }
+
+    /// Things go "in" a box, or "on" a counter.
+    pub fn preposition(&self) -> &'static str {
+        if self.surface {
+            "on"
+        } else {
+            "in"
+        }
+    }
+}
@@ -4,13 +4,14 @@ use std::cell::Ref;
 /// The game context provides a list of object for the current location.
 /// It's used primarily by the parser to determine which objects are available and what actions they support.
 /// TODO: Remove lifetimes, if not needed.
-///! I added lifetimes during one iteration to fix a borrow checker error.
+/// ! I added lifetimes during one iteration to fix a borrow checker error.
 #[derive(Default)]
 #[allow(dead_code)]
 pub struct GameContext<'a> {
     here: String,                              // current location
     locals: Vec<Ref<'a, Box<dyn GameObject>>>, // objects in current location
     inv: Vec<Ref<'a, Box<dyn GameObject>>>,    // objects carried to next location
+    rooms: Vec<String>,                        // every room in the world
 }
 
 #[allow(dead_code)]
@@ -19,8 +20,14 @@ impl<'a> GameContext<'a> {
         here: String,
         locals: Vec<Ref<'a, Box<dyn GameObject>>>,
         inv: Vec<Ref<'a, Box<dyn GameObject>>>,
+        rooms: Vec<String>,
     ) -> Self {
-        Self { here, locals, inv }
+        Self {
+            here,
+            locals,
+            inv,
+            rooms,
+        }
     }
 
     pub fn here(&self) -> String {
@@ -34,4 +41,17 @@ impl<'a> GameContext<'a> {
     pub fn inv(&self) -> &Vec<Ref<'a, Box<dyn GameObject>>> {
         &self.inv
     }
+
+    /// Is there a room with this name, anywhere in the world?
+    pub fn is_room(&self, name: &str) -> bool {
+        self.rooms.iter().any(|r| r == name)
+    }
+
+    /// Find a visible or carried object by name.
+    pub fn find(&self, name: &str) -> Option<&Ref<'a, Box<dyn GameObject>>> {
+        self.locals
+            .iter()
+            .chain(self.inv.iter())
+            .find(|o| o.name() == name)
+    }
 }
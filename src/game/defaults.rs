@@ -0,0 +1,50 @@
+use super::Action;
+
+/// What the game says when nothing handled an action on an object, by kind of action.
+/// Names are the ones the player used. Returns None when there's nothing sensible to say.
+/// ! Taking and dropping portable objects is done by the game itself. (See GameObject::portable.)
+pub fn response(action: &Action) -> Option<String> {
+    let text = match action {
+        Action::Attack(o, None) => format!("Attacking the {} won't help.", o),
+        Action::Attack(o, Some(i)) => format!("Hitting the {} with the {} won't help.", o, i),
+        Action::Climb(Some(o)) => format!("You can't climb the {}.", o),
+        Action::Drop(o, _) => format!("You're not carrying the {}.", o),
+        Action::Examine(Some(o)) => format!("You see nothing special about the {}.", o),
+        Action::Follow(Some(o)) => format!("The {} isn't going anywhere.", o),
+        Action::Knock(Some(o)) => format!("You knock on the {}. Nobody answers.", o),
+        Action::Light(o, _) => format!("You can't light the {}.", o),
+        Action::Listen(Some(o)) => format!("The {} makes no sound.", o),
+        Action::Open(o, _) => format!("You can't open the {}.", o),
+        Action::Close(o, _) => format!("You can't close the {}.", o),
+        Action::Lock(o, _) | Action::Unlock(o, _) => format!("The {} has no lock.", o),
+        Action::Press(o, _) => format!("Pressing the {} does nothing.", o),
+        Action::Read(o, _) => format!("There's nothing written on the {}.", o),
+        Action::Say(o, _) => format!("The {} doesn't answer.", o),
+        Action::Take(Some(o)) => format!("You can't take the {}.", o),
+        Action::Use(o, None) => format!("You can't use the {}.", o),
+        Action::Use(o, Some(i)) => format!("You can't use the {} on the {}.", i, o),
+        Action::Wear(Some(o)) => format!("You can't wear the {}.", o),
+        _ => return None,
+    };
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response() {
+        let knife = String::from("knife");
+        assert_eq!(
+            response(&Action::Climb(Some(knife.clone()))).unwrap(),
+            "You can't climb the knife."
+        );
+        assert_eq!(
+            response(&Action::Read(String::from("leaves"), None)).unwrap(),
+            "There's nothing written on the leaves."
+        );
+        assert_eq!(response(&Action::Describe(Some(knife))), None);
+        assert_eq!(response(&Action::Wait), None);
+    }
+}
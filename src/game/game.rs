@@ -1,32 +1,570 @@
-use super::parser::Parser;
-use super::{Action, GameAtlas, GameObject, Handled, NOWHERE};
+use super::combat::{self, Outcome, FISTS};
+use super::defaults;
+use super::map::{Automap, MAP_RADIUS};
+use super::output;
+use super::parser::{Parser, VERBS};
+use super::replay::{Recorder, Replay};
+use super::transcript::{Format, Transcript};
+use super::{
+    kind_of, Action, Direction, Event, GameAtlas, GameObject, Handled, Health, Lockable, Notify,
+    Puzzles, World, NOWHERE,
+};
+use crate::mini::guess::Guess;
+use serde::Serialize;
 use std::cell::Ref;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, BufRead};
+
+/// How much to say about a room when the player arrives.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verbosity {
+    Verbose,    // full description every time
+    Brief,      // full description on the first visit, then just the name and notable objects
+    Superbrief, // just the name and notable objects
+}
+
+/// Where the player stands, and how they're doing. Everything a program needs to follow the game.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Status {
+    pub room: String,           // where the player is
+    pub inventory: Vec<String>, // what they carry
+    pub objects: Vec<String>,   // what they can see here
+    pub score: i32,
+    pub turns: u32,
+}
+
+/// Something that changed in a turn, as far as the player can tell.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Moved(String, String), // from room, to room
+    Gained(String),        // now carried
+    Lost(String),          // no longer carried
+    Appeared(String),      // now in sight
+    Vanished(String),      // no longer in sight
+    Score(i32),            // points gained, or lost
+}
+
+/// Everything that came of one turn. (See Game::step.)
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct TurnResult {
+    pub output: String, // everything the player would have seen
+    #[serde(flatten)]
+    pub status: Status, // how things stand after the turn
+    pub changes: Vec<Change>,
+    pub ended: bool, // the game is over
+}
+
+impl Status {
+    /// What changed between this status and a later one.
+    pub fn changes(&self, after: &Status) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if self.room != after.room {
+            changes.push(Change::Moved(self.room.clone(), after.room.clone()));
+        }
+        let added = |from: &Vec<String>, to: &Vec<String>| -> Vec<String> {
+            to.iter().filter(|o| !from.contains(o)).cloned().collect()
+        };
+        changes.extend(
+            added(&self.inventory, &after.inventory)
+                .into_iter()
+                .map(Change::Gained),
+        );
+        changes.extend(
+            added(&after.inventory, &self.inventory)
+                .into_iter()
+                .map(Change::Lost),
+        );
+        changes.extend(
+            added(&self.objects, &after.objects)
+                .into_iter()
+                .map(Change::Appeared),
+        );
+        changes.extend(
+            added(&after.objects, &self.objects)
+                .into_iter()
+                .map(Change::Vanished),
+        );
+        if self.score != after.score {
+            changes.push(Change::Score(after.score - self.score));
+        }
+        changes
+    }
+}
 
 /// The game is the entry point in the game.
-///! The game is responsible for running the game loop and invoking the parser.
+/// ! The game is responsible for running the game loop and invoking the parser.
 pub struct Game {
     last_here: String, // last location
     atlas: GameAtlas,  // all objects in game
+    player: Health,    // player hit points
+    puzzles: Puzzles,  // puzzles, hints and points
+    score: i32,        // points earned, less hints
+    turns: u32,        // number of moves made
+    map: Automap,      // rooms visited and the ways between them
+    verbosity: Verbosity,
+    parser: Parser,       // reads, replays and records the player's commands
+    world: World,         // builds the world again, to restore a saved game
+    restoring: bool,      // replaying a saved game
+    name: Option<String>, // the player's name in a shared world, None when playing alone
+    remote: bool,         // played over the network, so no files are written or read for the player
 }
 
+/// Saved games go in here, and nowhere else.
+static SAVE_DIR: &str = "saves";
+
+/// Saved games are called this, unless the player names one.
+static SAVE_FILE: &str = "zork.sav";
+
 impl Game {
-    pub fn new(atlas: GameAtlas) -> Self {
-        Self {
+    pub fn new(world: World) -> Self {
+        let (atlas, puzzles) = world();
+        let mut game = Self {
             last_here: String::from(NOWHERE),
             atlas,
+            player: Health::new(10, 5),
+            puzzles: Puzzles::default(),
+            score: 0,
+            turns: 0,
+            map: Automap::default(),
+            verbosity: Verbosity::Brief,
+            parser: Parser::default(),
+            world,
+            restoring: false,
+            name: None,
+            remote: false,
+        };
+        game.puzzles.add_all(puzzles);
+        game
+    }
+
+    /// Restart the random numbers from the given seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.atlas.set_seed(seed);
+    }
+
+    /// Play these commands back before asking the player for more.
+    pub fn replay(&mut self, lines: Vec<String>) {
+        self.parser.replay(lines);
+    }
+
+    /// Join a shared world as the named player, starting in the given room.
+    /// From now on, the game is played in the shared atlas. (See play_shared.)
+    pub fn join(&mut self, atlas: &mut GameAtlas, name: String, room: String) {
+        atlas.add_player(name.clone(), room);
+        self.name = Some(name);
+        self.atlas = GameAtlas::default(); // the world this game was born with is no longer needed
+        self.with_atlas(atlas, |game| game.print_location());
+    }
+
+    /// Leave the shared world.
+    pub fn leave(&mut self, atlas: &mut GameAtlas) {
+        if let Some(name) = self.name.take() {
+            atlas.remove_player(name);
         }
     }
 
+    /// Take a turn in a shared world. Returns false when the game is over for this player.
+    pub fn play_shared(&mut self, atlas: &mut GameAtlas, line: String) -> bool {
+        self.with_atlas(atlas, |game| {
+            let playing = game.turn(line);
+            if playing {
+                game.print_location();
+            }
+            playing
+        })
+    }
+
+    /// Borrow the shared atlas for this player's turn. Everything else in the game is theirs alone.
+    fn with_atlas<T>(&mut self, atlas: &mut GameAtlas, turn: impl FnOnce(&mut Game) -> T) -> T {
+        std::mem::swap(&mut self.atlas, atlas);
+        if let Some(name) = self.name.clone() {
+            self.atlas.set_player(name);
+        }
+        let result = turn(self);
+        std::mem::swap(&mut self.atlas, atlas);
+        result
+    }
+
+    /// Play over the network. The player can't save, restore or write transcripts on the server.
+    pub fn set_remote(&mut self, remote: bool) {
+        self.remote = remote;
+    }
+
+    /// Read the player's commands from somewhere other than stdin.
+    pub fn set_input(&mut self, input: Box<dyn BufRead>) {
+        self.parser.set_input(input);
+    }
+
+    /// Record every command, and the seed, to a replay file.
+    pub fn record(&mut self, path: &str) -> io::Result<()> {
+        self.parser.record(path, self.atlas.seed())
+    }
+
     /// Inform the user of their impending doom. No one actually dies, though.
     pub fn print_death(&self) -> Handled {
-        println!("**That would lead to your untimely demise.**\n\nTry again?");
+        outln!("**That would lead to your untimely demise.**\n\nTry again?");
+        true
+    }
+
+    /// Game over, man. Game over!
+    pub fn print_game_over(&self) {
+        outln!("\n**** You have died ****\n");
+    }
+
+    /// Describe the player's wounds.
+    pub fn print_diagnosis(&self) -> Handled {
+        let (hp, max) = (self.player.hp(), self.player.max());
+        if hp == max {
+            outln!("You are in perfect health.");
+        } else if hp * 2 > max {
+            outln!("You have some light wounds. They will heal with time.");
+        } else {
+            outln!("You are seriously wounded. Another fight could be your last.");
+        }
+        outln!("({} of {} hit points)", hp, max);
+        true
+    }
+
+    /// Give the next hint for the puzzle the player is stuck on. Each new hint costs points.
+    pub fn print_hint(&mut self) -> Handled {
+        match self.puzzles.next_hint(&self.atlas) {
+            Some(hint) => {
+                outln!("Trying to {}?\n{}", hint.puzzle, hint.text);
+                if hint.cost > 0 {
+                    outln!("[That hint cost you {} point(s).]", hint.cost);
+                    self.add_score(-hint.cost);
+                }
+            }
+            None if self.puzzles.all_solved() => {
+                outln!("There's nothing left to solve. Enjoy the scenery.")
+            }
+            None => outln!("You don't seem to be stuck on anything. Have a look around."),
+        }
+        true
+    }
+
+    /// Print the score and number of moves.
+    pub fn print_score(&self) -> Handled {
+        outln!(
+            "Your score is {} (total of {} points), in {} moves.",
+            self.score,
+            self.puzzles.total(),
+            self.turns
+        );
+        outln!("(This is game number {}.)", self.atlas.seed());
+        true
+    }
+
+    /// Print the version, and the seed that makes this game what it is.
+    pub fn print_version(&self) -> Handled {
+        outln!("zork-rust version {}", env!("CARGO_PKG_VERSION"));
+        outln!(
+            "Game number {}. Start with --seed {} to play it again.",
+            self.atlas.seed(),
+            self.atlas.seed()
+        );
+        true
+    }
+
+    /// Take a break from adventuring for a game of guess.
+    pub fn play_guess(&mut self) -> Handled {
+        self.atlas.start_mini(None, Box::new(Guess::new()));
+        true
+    }
+
+    /// Get the path of a saved game, in the save directory.
+    /// Returns None if the name isn't a plain file name, so it could end up somewhere else.
+    fn save_path(name: Option<String>) -> Option<String> {
+        let name = match name {
+            None => SAVE_FILE.to_string(),
+            Some(name) if name.is_empty() || name.contains("..") => return None,
+            Some(name) if name.contains(std::path::is_separator) => return None,
+            Some(name) if name.contains('.') => name,
+            Some(name) => format!("{}.sav", name),
+        };
+        Some(format!("{}/{}", SAVE_DIR, name))
+    }
+
+    /// Save the game. A saved game is the seed and every command so far, just like a replay.
+    pub fn save(&mut self, name: Option<String>) -> Handled {
+        let path = match Game::save_path(name) {
+            Some(path) => path,
+            None => {
+                outln!("A saved game needs a plain name, like SAVE MYGAME.");
+                return true;
+            }
+        };
+        if let Err(e) = fs::create_dir_all(SAVE_DIR) {
+            outln!("Unable to save to {}: {}", SAVE_DIR, e);
+            return true;
+        }
+        // A SAVE among them is played back as nothing at all, so restoring puts you right back here.
+        match Recorder::create(&path, self.atlas.seed(), self.parser.history()) {
+            Ok(_) => outln!("Saved to {}.", path),
+            Err(e) => outln!("Unable to save to {}: {}", path, e),
+        }
         true
     }
 
-    /// Print a list of actions.
-    /// TODO: This should be a list of actions supported by the objects in view.
+    /// Restore a saved game. The world is rebuilt and every saved command is played again, quietly.
+    pub fn restore(&mut self, name: Option<String>) -> Handled {
+        let path = match Game::save_path(name) {
+            Some(path) => path,
+            None => {
+                outln!("A saved game has a plain name, like RESTORE MYGAME.");
+                return true;
+            }
+        };
+        let saved = match Replay::load(&path) {
+            Ok(saved) => saved,
+            Err(e) => {
+                outln!("Unable to restore from {}: {}", path, e);
+                return true;
+            }
+        };
+        self.rebuild(saved.seed, saved.lines);
+        outln!("Restored from {}.", path);
+        self.print_locals(self.atlas.here())
+    }
+
+    /// Take back the last command. Like restoring, the game is played again without it.
+    pub fn undo(&mut self) -> Handled {
+        let mut lines = self.parser.history().to_vec();
+        if lines.pop().is_none() {
+            outln!("There's nothing to undo.");
+            return true;
+        }
+        self.rebuild(self.atlas.seed(), lines);
+        outln!("[Your last command has been undone.]");
+        self.print_locals(self.atlas.here())
+    }
+
+    /// Build the world again, and quietly play these lines in it.
+    fn rebuild(&mut self, seed: u64, lines: Vec<String>) {
+        let (atlas, puzzles) = (self.world)();
+        self.atlas = atlas;
+        self.atlas.set_seed(seed);
+        self.puzzles = Puzzles::default();
+        self.puzzles.add_all(puzzles);
+        self.player = Health::new(10, 5);
+        self.score = 0;
+        self.turns = 0;
+        self.map = Automap::default();
+        self.last_here = String::from(NOWHERE);
+
+        output::mute(true);
+        self.restoring = true;
+        self.parser.restore(lines);
+        while self.parser.is_restoring() {
+            self.print_location();
+            if let Some(line) = self.parser.read_line() {
+                self.turn(line);
+            }
+        }
+        self.restoring = false;
+        self.print_location();
+        output::mute(false);
+    }
+
+    /// Exchange blows with a creature. The player strikes first, then the creature strikes back if it still can.
+    pub fn fight(&mut self, target: String, weapon: Option<String>) -> Handled {
+        let mut foe = match self.atlas.get_health(target.clone()) {
+            Some(health) => health,
+            None => return false,
+        };
+
+        let (stats, with) = match weapon {
+            None => (FISTS, String::from("your bare hands")),
+            Some(name) => match self.atlas.get_weapon(name.clone()) {
+                Some(stats) => (stats, format!("the {}", name)),
+                None => {
+                    outln!("You can't fight with the {}.", name);
+                    return true;
+                }
+            },
+        };
+
+        let outcome = combat::strike(self.atlas.rng(), &stats, &mut foe);
+        self.atlas.set_health(target.clone(), foe);
+        match outcome {
+            Outcome::Missed => outln!("You swing at the {} with {}, but miss.", target, with),
+            Outcome::Wounded(_) => outln!("You strike the {} with {}.", target, with),
+            Outcome::KnockedOut(_) => outln!(
+                "You strike the {} with {}. It crumples to the ground, senseless.",
+                target,
+                with
+            ),
+            Outcome::Killed(_) => {
+                if !self.atlas.invoke(Action::Die, target.clone()) {
+                    outln!("The {} is dead.", target);
+                }
+                self.atlas.remove_object(target);
+                return true;
+            }
+        }
+
+        if foe.is_conscious() {
+            let claws = self.atlas.get_weapon(target.clone()).unwrap_or(FISTS);
+            match combat::strike(self.atlas.rng(), &claws, &mut self.player) {
+                Outcome::Missed => outln!("The {} lunges at you, but misses.", target),
+                Outcome::Wounded(_) => outln!("The {} wounds you.", target),
+                Outcome::KnockedOut(_) => {
+                    outln!("The {} knocks you senseless.", target);
+                    self.pass_out();
+                }
+                Outcome::Killed(_) => outln!("The {} lands a fatal blow.", target),
+            }
+        }
+        true
+    }
+
+    /// Let time pass until the player wakes up.
+    fn pass_out(&mut self) {
+        while self.player.is_alive() && !self.player.is_conscious() {
+            outln!("Time passes...");
+            self.end_turn();
+        }
+        outln!("You come to, your head throbbing.");
+    }
+
+    /// Advance the clock one turn. Wounds heal, stunned creatures wake up, and things nearby get to act.
+    /// Returns true if anything happened that the player should stop and notice.
+    /// ! A shared world keeps its own time, and checks its own puzzles. Only the player's clock moves here.
+    /// (See SharedWorld::play.)
+    pub fn end_turn(&mut self) -> bool {
+        let alone = self.name.is_none();
+        let mut eventful = alone && self.atlas.tick_here();
+        eventful |= alone && self.atlas.tick_events();
+        self.turns += 1;
+        let mut points = self.atlas.take_points();
+        if alone {
+            points += self.puzzles.update(&self.atlas);
+        }
+        eventful |= self.award(points);
+
+        self.player.tick();
+        if alone {
+            let woke = self.atlas.heal_all();
+            eventful |= self.print_woke(&woke);
+        }
+        eventful |= self
+            .atlas
+            .publish(Event::TurnEnded(self.atlas.player(), self.turns));
+        eventful
+    }
+
+    /// Add the points to the score, and say so. Returns true if there were any.
+    fn award(&mut self, points: i32) -> bool {
+        if points > 0 {
+            outln!("[Your score has gone up by {} points.]", points);
+        } else if points < 0 {
+            outln!("[Your score has gone down by {} points.]", -points);
+        }
+        if points != 0 {
+            self.add_score(points);
+        }
+        points != 0
+    }
+
+    /// Say which of the creatures that just woke up are here. Returns true if any are.
+    fn print_woke(&self, woke: &[String]) -> bool {
+        let here = self.atlas.here();
+        let mut seen = false;
+        for name in woke {
+            if self
+                .atlas
+                .get(name.clone())
+                .is_some_and(|o| o.loc() == here)
+            {
+                outln!("The {} stirs and shakes itself awake.", name);
+                seen = true;
+            }
+        }
+        seen
+    }
+
+    /// Award points in a shared world, for a puzzle this player just solved.
+    pub fn award_shared(&mut self, atlas: &mut GameAtlas, points: i32) {
+        self.with_atlas(atlas, |game| game.award(points));
+    }
+
+    /// Let the shared world's time pass, as this player sees it: things near them act, unless they already
+    /// have this turn, and they see the creatures here that `woke` up. (See SharedWorld::tick.)
+    pub fn tick_shared(
+        &mut self,
+        atlas: &mut GameAtlas,
+        ticked: &mut BTreeSet<String>,
+        woke: &[String],
+    ) {
+        self.with_atlas(atlas, |game| {
+            game.atlas.tick_near(ticked);
+            game.print_woke(woke);
+        });
+    }
+
+    /// Add points to the score, or take them away, and let everyone know.
+    fn add_score(&mut self, points: i32) {
+        self.score += points;
+        self.atlas
+            .publish(Event::ScoreChanged(self.atlas.player(), points, self.score));
+    }
+
+    /// Print the actions supported by the objects in view, and the ways out of here.
     pub fn print_help(&self) -> Handled {
-        println!("Try these commands:\nLOOK\nMOVE\nTAKE\nDROP\nATTACK\nINV\nQUIT");
+        let context = self.atlas.get_context();
+        let objects: Vec<_> = context
+            .locals()
+            .iter()
+            .chain(context.inv().iter())
+            .collect();
+
+        outln!("Here you can:");
+        for action in Action::all_for("") {
+            let mut names: Vec<String> = objects
+                .iter()
+                .filter(|o| o.can_do(&action.set_object(o.name())))
+                // No need to take what's already carried.
+                .filter(|o| !matches!(action, Action::Take(_)) || !self.atlas.is_carried(&o.name()))
+                .map(|o| format!("the {}", kind_of(&o.name())))
+                .collect();
+            if !names.is_empty() {
+                names.sort();
+                names.dedup();
+                outln!("  {} {}", action.verb().to_uppercase(), names.join(", "));
+            }
+        }
+
+        if let Some(room) = self.atlas.get(context.here()) {
+            let exits: Vec<String> = Direction::all()
+                .into_iter()
+                .map(Action::Go)
+                .filter(|go| room.can_do(go))
+                .map(|go| format!("{:?}", go.get_direction().unwrap()).to_uppercase())
+                .collect();
+            if !exits.is_empty() {
+                outln!("  GO {}", exits.join(", "));
+            }
+        }
+
+        outln!(
+            "You can always LOOK, WAIT, INVENTORY, DIAGNOSE, HINT, SCORE, SAVE, RESTORE or QUIT."
+        );
+        outln!("Type VERBS for a list of every word I know.");
+        true
+    }
+
+    /// Print every verb the parser understands, with its synonyms.
+    pub fn print_verbs(&self) -> Handled {
+        for synonyms in VERBS.iter() {
+            let (verb, others) = synonyms.split_first().unwrap();
+            if others.is_empty() {
+                outln!("{}", verb.to_uppercase());
+            } else {
+                outln!("{} ({})", verb.to_uppercase(), others.join(", "));
+            }
+        }
         true
     }
 
@@ -36,52 +574,278 @@ impl Game {
     }
 
     pub fn print_locals(&mut self, location: String) -> Handled {
-        print!("\n{}\n", location.clone().to_uppercase());
+        out!("\n{}\n", location.clone().to_uppercase());
 
         self.atlas.invoke_here(Action::Describe(None));
-        let locals = self.to_names(&self.atlas.get_locals(location));
+        let locals = self.to_names(&self.atlas.get_locals(location.clone()));
         if locals.is_empty() {
-            println!("You see nothing of interest.");
+            outln!("You see nothing of interest.");
         } else {
             self.atlas.describe_all(locals);
         }
+        self.print_contents(location);
+        self.print_players();
+        true
+    }
+
+    /// Print what's in (or on) each open container in the location.
+    fn print_contents(&mut self, location: String) {
+        let containers: Vec<_> = self
+            .atlas
+            .get_locals(location)
+            .iter()
+            .filter_map(|o| o.container().map(|c| (o.name(), c)))
+            .filter(|(_, c)| c.open)
+            .collect();
+        for (name, _) in containers {
+            self.print_contained(name);
+        }
+    }
+
+    /// Print what's in (or on) the container, if anything.
+    fn print_contained(&mut self, name: String) {
+        let container = match self.atlas.get(name.clone()).and_then(|o| o.container()) {
+            Some(container) => container,
+            None => return,
+        };
+        let contents = self.atlas.contents(&name);
+        if contents.is_empty() {
+            return;
+        }
+        let at = if container.surface { "On" } else { "In" };
+        outln!("{} the {}:", at, name);
+        self.atlas
+            .describe_all(contents.into_iter().map(Some).collect());
+    }
+
+    /// Print the other players here, if any.
+    fn print_players(&self) {
+        for name in self.atlas.players_here() {
+            outln!("{} is here.", name);
+        }
+    }
+
+    /// Print the location name and the objects worth mentioning: things you can take, and creatures.
+    pub fn print_brief(&mut self, location: String) -> Handled {
+        out!("\n{}\n", location.clone().to_uppercase());
+
+        let notable: Vec<Option<String>> = self
+            .atlas
+            .get_locals(location)
+            .iter()
+            .filter(|o| o.portable() || o.health().is_some())
+            .map(|o| Some(o.name()))
+            .collect();
+        self.atlas.describe_all(notable);
+        self.print_players();
+        true
+    }
+
+    /// Change how much is said about each room on arrival.
+    pub fn set_verbosity(&mut self, verbosity: Verbosity) -> Handled {
+        self.verbosity = verbosity;
+        match verbosity {
+            Verbosity::Verbose => {
+                outln!("Maximum verbosity. Rooms are always described in full.")
+            }
+            Verbosity::Brief => {
+                outln!("Brief descriptions. Rooms are described in full on your first visit.")
+            }
+            Verbosity::Superbrief => {
+                outln!("Superbrief descriptions. Only room names and notable objects are given. LOOK for more.")
+            }
+        }
         true
     }
 
     /// Print the current location and all objects in the location.
     /// Only print the location if it has changed since the last invocation.
+    /// How much is printed depends on the verbosity and whether the room was visited before.
     pub fn print_location(&mut self) -> Handled {
         let here = self.atlas.here();
         if here == self.last_here {
             return false;
         }
-
         self.atlas.invoke(
             Action::Leave(self.last_here.clone()),
             self.last_here.clone(),
         );
+        if self.last_here != NOWHERE {
+            let player = self.atlas.player();
+            self.atlas
+                .publish(Event::Left(player, self.last_here.clone()));
+        }
 
         self.last_here = here.clone();
+        let first_visit = !self.map.is_visited(&here);
+        self.map.visit(here.clone());
+        self.map.learn(self.atlas.get_exits());
 
         self.atlas.invoke_here(Action::Arrive(here.clone()));
+        match self.verbosity {
+            Verbosity::Verbose => self.print_locals(here.clone()),
+            Verbosity::Brief if first_visit => self.print_locals(here.clone()),
+            Verbosity::Brief | Verbosity::Superbrief => self.print_brief(here.clone()),
+        };
+        // Everyone else gets to react once the player has had a look around.
+        self.atlas
+            .publish(Event::Arrived(self.atlas.player(), here));
+        true
+    }
 
-        self.print_locals(here.clone())
+    /// Start recording a transcript. It's exported in the given format when recording stops.
+    pub fn start_script(&mut self, format: Format) -> Handled {
+        if output::is_recording() {
+            outln!("You are already recording a transcript.");
+            return true;
+        }
+        match Transcript::create(format) {
+            Ok(transcript) => {
+                outln!("Recording a transcript to {}.", transcript.path().display());
+                output::start_transcript(transcript);
+            }
+            Err(e) => outln!("Unable to start a transcript: {}", e),
+        }
+        true
+    }
+
+    /// Stop recording the transcript, and export it.
+    pub fn stop_script(&mut self) -> Handled {
+        match output::stop_transcript() {
+            None => outln!("You aren't recording a transcript."),
+            Some(transcript) => match transcript.finish() {
+                Ok(paths) => {
+                    let names: Vec<String> =
+                        paths.iter().map(|p| p.display().to_string()).collect();
+                    outln!("Transcript saved to {}.", names.join(" and "));
+                }
+                Err(e) => outln!("Unable to save the transcript: {}", e),
+            },
+        }
+        true
+    }
+
+    /// Draw the explored rooms around the player.
+    pub fn print_map(&self) -> Handled {
+        out!("{}", self.map.render(&self.atlas.here(), Some(MAP_RADIUS)));
+        true
+    }
+
+    /// Try to go in the given direction, and remember the way if it worked.
+    pub fn go(&mut self, action: Action) -> Handled {
+        let from = self.atlas.here();
+        if let Some(door) = action.get_direction().and_then(|dir| self.door_to(dir)) {
+            if !self.open_door(door) {
+                return true;
+            }
+        }
+        // The room knows its own exits. Gates and doors in the room may offer more.
+        let others = self.to_names(&self.atlas.get_locals_here());
+        if !self.atlas.invoke_here(action.clone())
+            && !self.atlas.invoke_until(action.clone(), others)
+        {
+            outln!("You can't go that way.");
+            return true;
+        }
+        let to = self.atlas.here();
+        if let (Some(dir), true) = (action.get_direction(), to != from) {
+            self.map.link(from, dir, to);
+        }
+        true
+    }
+
+    /// Find the door on the way out of here in the direction, if there is one.
+    fn door_to(&self, dir: Direction) -> Option<String> {
+        let here = self.atlas.here();
+        let to = self
+            .atlas
+            .get_exits()
+            .into_iter()
+            .find(|(room, _)| *room == here)
+            .and_then(|(_, exits)| exits.into_iter().find(|(d, _)| *d == dir))
+            .map(|(_, to)| to)?;
+        self.atlas.door_between(&here, &to)
+    }
+
+    /// Get ready to go through the door: open it, if it's closed.
+    /// Returns false if it's locked, and there's no getting through.
+    fn open_door(&mut self, door: String) -> bool {
+        let mut lock = match self.atlas.get_lockable(door.clone()) {
+            Some(lock) => lock,
+            None => return true,
+        };
+        if lock.open {
+            return true;
+        }
+        if lock.locked {
+            outln!("The {} is locked.", door);
+            return false;
+        }
+        outln!("(first opening the {})", door);
+        lock.open = true;
+        self.atlas.set_lockable(door, lock);
+        true
+    }
+
+    /// Is there a creature here, awake and watching?
+    fn is_anyone_here(&self) -> bool {
+        self.atlas
+            .get_locals_here()
+            .iter()
+            .any(|o| o.health().is_some_and(|h| h.is_conscious()))
+    }
+
+    /// Walk to a room visited before, one move per turn.
+    /// Stops early if a move fails, someone is here, or something happens along the way.
+    pub fn travel(&mut self, room: String) -> Handled {
+        let here = self.atlas.here();
+        if room == here {
+            outln!("You're already in the {}.", room);
+            return true;
+        }
+        let path = match self.map.path(&here, &room) {
+            Some(path) => path,
+            None => {
+                outln!("You don't know the way to the {}.", room);
+                return true;
+            }
+        };
+
+        for (i, (dir, next)) in path.into_iter().enumerate() {
+            // The last move ends its turn in the game loop.
+            if i > 0 && self.end_turn() {
+                outln!("You stop to take stock.");
+                return true;
+            }
+            self.go(Action::Go(dir));
+            self.print_location();
+            if self.atlas.here() != next {
+                outln!("You seem to have lost your way.");
+                return true;
+            }
+            if self.atlas.here() != room && self.is_anyone_here() {
+                outln!("You stop. You are not alone.");
+                return true;
+            }
+        }
+        true
     }
 
     /// Print the inventory objects.
     pub fn print_inventory(&mut self) -> Handled {
         let inventory = self.to_names(&self.atlas.get_inventory());
         if inventory.is_empty() {
-            println!("You are not carrying anything.");
+            outln!("You are not carrying anything.");
             return true;
         } else {
-            println!("You are carrying:");
+            outln!("You are carrying:");
             self.atlas.invoke_all(Action::Describe(None), inventory);
         }
         true
     }
 
     /// Invoke the action on the specified objects in order of PRSI, PRSO, HERE.
+    /// ! The room and what the player carries react after, if it was handled. (See play for before.)
     pub fn try_invoke(
         &mut self,
         action: Action,
@@ -89,66 +853,434 @@ impl Game {
         prsi: Option<String>,
     ) -> Handled {
         let objects = vec![prsi, prso, Some(self.atlas.here())];
-        self.atlas.invoke_until(action, objects)
+        let real = self.with_real_names(&action);
+        let handled = self.atlas.invoke_until(real.clone(), objects) || self.play_default(&action);
+        if handled {
+            self.atlas.after(&real);
+        }
+        handled
     }
 
-    /// Run the game loop.
-    pub fn run(&mut self) {
-        let parser = Parser::default();
+    /// Get the action with the objects in it called by their real names, whatever the player called them.
+    /// Objects only ever see real names. Words that aren't objects' names, like what's said, are left as they are.
+    fn with_real_names(&self, action: &Action) -> Action {
+        let mut action = action.clone();
+        if let Some(name) = action.get_object().and_then(|o| self.atlas.resolve(o)) {
+            action = action.set_object(name);
+        }
+        if let Some(name) = action
+            .get_indirect_object()
+            .and_then(|o| self.atlas.resolve(o))
+        {
+            action = action.set_indirect_object(name);
+        }
+        action
+    }
 
-        loop {
-            self.print_location();
+    /// Answer for an object when nothing handled the action: take or drop it if it's portable,
+    /// or say why nothing happens. Returns false if the object isn't around, or opted out.
+    fn play_default(&mut self, action: &Action) -> Handled {
+        let name = match action.get_object().and_then(|o| self.atlas.resolve(o)) {
+            Some(name) => name,
+            None => return false,
+        };
+        let (portable, lockable) = match self.atlas.get(name.clone()) {
+            Some(o) if o.has_default(action) => (o.portable(), o.lockable()),
+            _ => return false,
+        };
+        let carried = self.atlas.is_carried(&name);
+
+        match action {
+            Action::Take(_) if portable && carried => outln!("You already have that."),
+            Action::Take(_) if portable => {
+                self.atlas.move_inventory(name);
+                outln!("Taken.");
+            }
+            Action::Drop(_, None) if portable && carried => {
+                self.atlas.move_local(name);
+                outln!("Dropped.");
+            }
+            Action::Drop(_, None) if carried => outln!("You can't let go of that."),
+            Action::Drop(object, Some(target)) if carried => self.put(name, object, target),
+            Action::Open(..) | Action::Close(..) | Action::Lock(..) | Action::Unlock(..)
+                if lockable.is_some() =>
+            {
+                self.operate(name, lockable.unwrap_or_default(), action)
+            }
+            _ => match defaults::response(action) {
+                Some(text) => outln!("{}", text),
+                None => return false,
+            },
+        }
+        true
+    }
+
+    /// Can the player get at everything the action names, and do they carry what it needs carried?
+    /// Says why not, if they can't.
+    fn is_within_reach(&self, action: &Action) -> bool {
+        // Only what's carried can be dropped, put away or fought with.
+        match action {
+            Action::Drop(object, _) if !self.carries(object) => {
+                outln!("You're not carrying the {}.", object);
+                return false;
+            }
+            Action::Attack(_, Some(weapon)) if !self.carries(weapon) => {
+                outln!("You don't have the {}.", weapon);
+                return false;
+            }
+            _ => {}
+        }
+        let objects = [action.get_object(), action.get_indirect_object()];
+        let names: Vec<String> = objects
+            .into_iter()
+            .flatten()
+            .filter_map(|o| self.atlas.resolve(o))
+            .collect();
+        match names.iter().find(|n| !self.atlas.is_reachable(n)) {
+            Some(name) => {
+                self.print_out_of_reach(name);
+                false
+            }
+            None => true,
+        }
+    }
 
-            let action = parser.input_action(&self.atlas.get_context());
+    /// Does the player carry the object, by any of its names?
+    fn carries(&self, object: &str) -> bool {
+        self.atlas
+            .resolve(object.to_string())
+            .is_some_and(|name| self.atlas.is_carried(&name))
+    }
 
-            let handled: Handled = match action.clone() {
-                Action::Die => self.print_death(),
-                Action::Help => self.print_help(),
-                Action::Inventory => self.print_inventory(),
-                Action::Quit => break,
-                Action::Go(_) => {
-                    self.atlas.invoke_here(action) || {
-                        println!("You can't go that way.");
-                        true
-                    }
+    /// Say why the player can't get at the object.
+    fn print_out_of_reach(&self, name: &str) {
+        match self.atlas.closed_container(name) {
+            Some(container) => outln!("The {} is closed.", container),
+            None => outln!("You don't see any {} here.", kind_of(name)),
+        }
+    }
+
+    /// Put the carried object in (or on) the target, if it's an open container with room to spare.
+    /// Names the player used are for what to say, `name` is the object's real name.
+    fn put(&mut self, name: String, object: &str, target: &str) {
+        let container = match self.atlas.resolve(target.to_string()) {
+            Some(container) if self.atlas.is_reachable(&container) => container,
+            _ => {
+                outln!("You don't see any {} here.", target);
+                return;
+            }
+        };
+        let properties = self
+            .atlas
+            .get(container.clone())
+            .and_then(|o| o.container());
+        match properties {
+            None => outln!("You can't put anything in the {}.", target),
+            Some(c) if container == name || self.atlas.is_inside(&container, &name) => {
+                outln!("You can't put the {} {} itself.", object, c.preposition())
+            }
+            Some(c) if !c.open => outln!("The {} is closed.", target),
+            Some(c) if self.atlas.contents(&container).len() >= c.capacity => {
+                outln!("There's no more room {} the {}.", c.preposition(), target)
+            }
+            Some(c) => {
+                self.atlas.move_into(name, container);
+                outln!("You put the {} {} the {}.", object, c.preposition(), target);
+            }
+        }
+    }
+
+    /// Open, close, lock or unlock something that has a lock: a door, a chest, the breadbox.
+    fn operate(&mut self, name: String, mut lock: Lockable, action: &Action) {
+        let object = action.get_object().unwrap_or_default();
+        let key = action.get_indirect_object();
+        match action {
+            Action::Open(..) if lock.open => outln!("The {} is already open.", object),
+            Action::Open(..) if lock.locked && key.is_none() => {
+                outln!("The {} is locked.", object)
+            }
+            Action::Open(..) if lock.locked => {
+                if let Some(key) = self.find_key(&lock, key) {
+                    lock.locked = false;
+                    lock.open = true;
+                    outln!("You unlock the {} with the {}, and open it.", object, key);
                 }
-                Action::Wait => self.atlas.invoke_here(action),
-                Action::Describe(prso) | Action::Examine(prso) => match prso {
-                    None => self.print_locals(self.atlas.here()),
-                    Some(name) => self.try_invoke(action, Some(name), None),
-                },
-                Action::Climb(prso)
-                | Action::Listen(prso)
-                | Action::Follow(prso)
-                | Action::Take(prso) => self.try_invoke(action, prso, None),
-                Action::Attack(prso, prsi)
-                | Action::Drop(prso, prsi)
-                | Action::Light(prso, prsi)
-                | Action::Open(prso, prsi)
-                | Action::Read(prso, prsi)
-                | Action::Say(prso, prsi)
-                | Action::Use(prso, prsi) => self.try_invoke(action, Some(prso), prsi),
-                Action::UnknownAction(action) => {
-                    println!("I don't know how to {}. Have you tried 'HELP'?", action);
-                    true
+            }
+            Action::Open(..) => {
+                lock.open = true;
+                outln!("You open the {}.", object);
+            }
+            Action::Close(..) if !lock.open => outln!("The {} is already closed.", object),
+            Action::Close(..) => {
+                lock.open = false;
+                outln!("You close the {}.", object);
+            }
+            _ if !lock.has_lock() => outln!("The {} has no lock.", object),
+            Action::Unlock(..) if !lock.locked => outln!("The {} isn't locked.", object),
+            Action::Lock(..) if lock.locked => outln!("The {} is already locked.", object),
+            Action::Lock(..) if lock.open => outln!("You'll have to close the {} first.", object),
+            _ => {
+                if let Some(key) = self.find_key(&lock, key) {
+                    lock.locked = matches!(action, Action::Lock(..));
+                    let verb = if lock.locked { "lock" } else { "unlock" };
+                    outln!("You {} the {} with the {}.", verb, object, key);
                 }
-                Action::AmbiguousObject(objects) => {
-                    println!("That action could apply to: {}.", objects.join(", "));
+            }
+        }
+
+        let opened = lock.open;
+        if Some(&lock) != self.atlas.get_lockable(name.clone()).as_ref() {
+            self.atlas.set_lockable(name.clone(), lock);
+            if opened {
+                self.print_contained(name);
+            }
+        }
+    }
+
+    /// Find the carried key for the lock: the one the player named, or any that fits.
+    /// Says why not, if there isn't one.
+    fn find_key(&self, lock: &Lockable, key: Option<String>) -> Option<String> {
+        match key {
+            Some(key) => match self.atlas.resolve(key.clone()) {
+                Some(name) if !self.atlas.is_carried(&name) => {
+                    outln!("You don't have the {}.", key);
+                    None
+                }
+                Some(name) if lock.keys.contains(&name) => Some(key),
+                _ => {
+                    outln!("The {} doesn't fit the lock.", key);
+                    None
+                }
+            },
+            None => match lock.keys.iter().find(|k| self.atlas.is_carried(k)) {
+                Some(name) => {
+                    outln!("(with the {})", name);
+                    Some(name.clone())
+                }
+                None => {
+                    outln!("You don't have a key that fits.");
+                    None
+                }
+            },
+        }
+    }
+
+    /// Carry out one action. Prints "Nothing happens." if nothing handled it.
+    /// ! Anything done in the world can be stopped or replaced by the room and what the player carries, first.
+    fn play(&mut self, action: Action) -> Handled {
+        if !action.is_meta() {
+            // Nobody gets to handle what the player can't get at.
+            if !self.is_within_reach(&action) {
+                return true;
+            }
+            match self.atlas.before(&self.with_real_names(&action)) {
+                Notify::Unhandled => {}
+                // Whatever comes of the other action is all that happens.
+                Notify::Instead(action) => return self.play(action),
+                _ => return true,
+            }
+        }
+        let handled: Handled = match action.clone() {
+            Action::Die => self.print_death(),
+            Action::Diagnose => self.print_diagnosis(),
+            Action::Help => self.print_help(),
+            Action::Hint => self.print_hint(),
+            Action::Map => self.print_map(),
+            Action::Verbose => self.set_verbosity(Verbosity::Verbose),
+            Action::Brief => self.set_verbosity(Verbosity::Brief),
+            Action::Superbrief => self.set_verbosity(Verbosity::Superbrief),
+            Action::Save(_) | Action::Restore(_) if self.name.is_some() => {
+                outln!("You can't save or restore a world you share with others.");
+                true
+            }
+            Action::Save(_) | Action::Restore(_) | Action::Script(_) if self.remote => {
+                outln!("Saved games and transcripts are only for games played on this computer.");
+                true
+            }
+            Action::Script(format) => match format.map(|f| Format::from_name(&f)) {
+                None => self.start_script(Format::Text),
+                Some(Some(format)) => self.start_script(format),
+                Some(None) => {
+                    outln!("I can only write transcripts as TEXT, MARKDOWN or HTML.");
                     true
                 }
-                _ => false,
+            },
+            Action::Unscript => self.stop_script(),
+            Action::Score => self.print_score(),
+            Action::Guess => self.play_guess(),
+            Action::Version => self.print_version(),
+            Action::Save(name) => self.save(name),
+            Action::Restore(name) => self.restore(name),
+            Action::Verbs => self.print_verbs(),
+            Action::Inventory => self.print_inventory(),
+            Action::Go(_) => self.go(action),
+            Action::Travel(room) => self.travel(room),
+            Action::Wait => self.atlas.invoke_here(action),
+            Action::Describe(prso) | Action::Examine(prso) => match prso {
+                None => self.print_locals(self.atlas.here()),
+                Some(name) => self.try_invoke(action, Some(name), None),
+            },
+            Action::Climb(prso)
+            | Action::Listen(prso)
+            | Action::Follow(prso)
+            | Action::Knock(prso)
+            | Action::Take(prso)
+            | Action::Wear(prso) => self.try_invoke(action, prso, None),
+            Action::Attack(prso, prsi) if self.atlas.get_health(prso.clone()).is_some() => {
+                self.fight(prso, prsi)
+            }
+            Action::Attack(prso, prsi)
+            | Action::Drop(prso, prsi)
+            | Action::Light(prso, prsi)
+            | Action::Open(prso, prsi)
+            | Action::Close(prso, prsi)
+            | Action::Lock(prso, prsi)
+            | Action::Unlock(prso, prsi)
+            | Action::Read(prso, prsi)
+            | Action::Press(prso, prsi)
+            | Action::Say(prso, prsi)
+            | Action::Use(prso, prsi) => self.try_invoke(action, Some(prso), prsi),
+            Action::UnknownAction(action) => {
+                outln!("I don't know how to {}. Have you tried 'HELP'?", action);
+                true
+            }
+            Action::UnknownDirection(direction) => {
+                outln!("I don't know which way {} is.", direction);
+                true
+            }
+            Action::AmbiguousObject(objects) => {
+                outln!("That action could apply to: {}.", objects.join(", "));
+                true
+            }
+            _ => false,
+        };
+        if !handled {
+            outln!("Nothing happens.");
+        }
+        handled
+    }
+
+    /// Play one line of input: a move in a mini game, or a command.
+    /// Returns false when the game is over.
+    fn turn(&mut self, line: String) -> bool {
+        if self.atlas.is_playing() {
+            // The clock stands still until the mini game is over.
+            if self.atlas.play_mini(&line).is_none() {
+                return true;
+            }
+        } else {
+            let action = self.parser.parse_line(line, &self.atlas.get_context());
+            match action {
+                Action::Quit => return false,
+                // Don't save, restore or start writing transcripts while restoring.
+                Action::Save(_) | Action::Restore(_) | Action::Script(_) | Action::Unscript
+                    if self.restoring =>
+                {
+                    return true;
+                }
+                // Asking about the game happens outside of game time.
+                action if action.is_meta() => {
+                    self.play(action);
+                    return true;
+                }
+                _ => {
+                    self.play(action);
+                }
+            }
+        }
+
+        if !self.player.is_alive() {
+            self.print_game_over();
+            return false;
+        }
+        self.end_turn();
+        true
+    }
+
+    /// Get the player's status.
+    pub fn status(&self) -> Status {
+        let names = |objects| self.to_names(&objects).into_iter().flatten().collect();
+        Status {
+            room: self.atlas.here(),
+            inventory: names(self.atlas.get_inventory()),
+            objects: names(self.atlas.get_locals_here()),
+            score: self.score,
+            turns: self.turns,
+        }
+    }
+
+    /// Look around before the first step: the opening text, and where the player starts.
+    pub fn start(&mut self) -> TurnResult {
+        self.capture(|game| {
+            game.print_location();
+            true
+        })
+    }
+
+    /// Play one command, and get back everything that came of it.
+    /// Use this to drive the game yourself, one turn at a time, instead of calling run.
+    pub fn step(&mut self, input: &str) -> TurnResult {
+        self.capture(|game| game.play_line(input.to_string()))
+    }
+
+    /// Take back the last step.
+    pub fn step_back(&mut self) -> TurnResult {
+        self.capture(|game| game.undo())
+    }
+
+    /// Capture what the player sees and what changes while `turn` is played.
+    /// `turn` returns false when the game is over. Use this to save or restore without a command line.
+    pub fn capture(&mut self, turn: impl FnOnce(&mut Game) -> bool) -> TurnResult {
+        let before = self.status();
+        let (playing, output) = output::capture(|| turn(self));
+        let status = self.status();
+        TurnResult {
+            output,
+            changes: before.changes(&status),
+            status,
+            ended: !playing,
+        }
+    }
+
+    /// Play a line given by the caller, instead of reading one, and look around afterwards.
+    /// Returns false when the game is over.
+    fn play_line(&mut self, line: String) -> bool {
+        let line = self.parser.accept(line);
+        let playing = self.turn(line);
+        if playing {
+            self.print_location();
+        }
+        playing
+    }
+
+    /// Run the game loop.
+    pub fn run(&mut self) {
+        loop {
+            self.print_location();
+
+            // Running out of input is the same as quitting.
+            let line = match self.parser.read_line() {
+                Some(line) => line,
+                None => break,
             };
-            if !handled {
-                println!("Nothing happens.");
+            if !self.turn(line) {
+                break;
             }
         }
+
+        if output::is_recording() {
+            self.stop_script();
+        }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "world"))]
 #[allow(dead_code)]
 mod tests {
-    use crate::game::{objects::forest, objects::kitchen, GameAtlas, Handled, Location, Notify};
+    use crate::game::{GameAtlas, Handled, Location, Notify, INVENTORY};
+    use crate::world::{forest, kitchen};
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     use super::*;
 
@@ -176,12 +1308,12 @@ mod tests {
             action.print();
 
             if let Some(prso) = action.get_object() {
-                println!("prso: {}", prso.clone());
+                outln!("prso: {}", prso.clone());
                 self.prso = prso;
             }
 
             if let Some(prsi) = action.get_indirect_object() {
-                println!("prsi: {}", prsi.clone());
+                outln!("prsi: {}", prsi.clone());
                 self.prsi = prsi;
             }
 
@@ -221,6 +1353,14 @@ mod tests {
                         self.prso = object_name;
                         handled = true;
                     }
+
+                    Notify::Play(game) => {
+                        self.loc = game.name();
+                        handled = true;
+                    }
+
+                    Notify::Effects(effects) => handled = !effects.is_empty(),
+                    Notify::Instead(_) => handled = false,
                 }
             }
             handled
@@ -236,4 +1376,665 @@ mod tests {
         atlas.add_all(vec);
         atlas
     }
+
+    #[test]
+    fn test_before_and_after() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.atlas.move_inventory(String::from(kitchen::GOLDRING));
+
+        assert!(game.step("wear ring").output.contains("onto your pinky"));
+        game.step("kick leaves");
+        let result = game.step("take key");
+        assert!(result.output.contains("pass right through the key"));
+        assert!(result.status.inventory.iter().all(|name| name != "key"));
+
+        let result = game.step("examine leaves");
+        assert!(result.output.contains("faint and far away"));
+
+        // Taking the ring you wear takes it off instead.
+        assert!(game.step("take ring").output.contains("pull the ring off"));
+        assert!(game.step("take key").output.contains("You take the key."));
+    }
+
+    #[test]
+    fn test_wear_ring() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.atlas.move_inventory(String::from(kitchen::GOLDRING));
+
+        game.step("wear ring");
+        let result = game.step("wear ring");
+        assert!(result.output.contains("You're already wearing it."));
+        let result = game.step("take gold ring");
+        assert!(
+            result.output.contains("pull the ring off"),
+            "{}",
+            result.output
+        );
+
+        // Dropping the ring takes it off, so it isn't still worn when it's picked up again.
+        game.step("wear ring");
+        let result = game.step("drop ring");
+        assert!(result.output.contains("off your pinky"));
+        game.step("take ring");
+        game.step("kick leaves");
+        assert!(game.step("take key").output.contains("You take the key."));
+    }
+
+    #[test]
+    fn test_default_responses() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        let result = game.step("read leaves");
+        assert!(result
+            .output
+            .contains("There's nothing written on the leaves."));
+        assert!(game
+            .step("light leaves")
+            .output
+            .contains("You can't light the leaves."));
+        assert!(game
+            .step("climb leaves")
+            .output
+            .contains("You can't climb the leaves."));
+        // The raccoon opted out of this one.
+        assert!(game
+            .step("listen raccoon")
+            .output
+            .contains("Nothing happens."));
+
+        game.step("kick leaves");
+        game.step("take key");
+        let result = game.step("drop key");
+        assert!(result.output.contains("Dropped."));
+        assert_eq!(result.changes[0], Change::Lost(String::from("key")));
+        assert!(game.step("take key").output.contains("You take the key."));
+        assert!(game
+            .step("take raccoon")
+            .output
+            .contains("You can't take the raccoon."));
+    }
+
+    #[test]
+    fn test_put() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("kick leaves");
+        game.step("take key");
+        game.step("go north");
+
+        let result = game.step("put key in breadbox");
+        assert!(result.output.contains("The breadbox is closed."));
+        let result = game.step("put key on counter");
+        assert!(result.output.contains("You put the key on the counter."));
+        assert_eq!(result.changes, vec![Change::Lost(String::from("key"))]);
+        assert!(game.step("look").output.contains("On the counter:"));
+        assert!(game.step("take key").output.contains("You take the key."));
+
+        // The sink has its own ideas about where things go.
+        // The sink knows the ring by its real name, whatever the player calls it.
+        game.atlas.move_inventory(String::from(kitchen::GOLDRING));
+        let result = game.step("put gold in sink");
+        assert!(result.output.contains("circles the drain"));
+        assert!(game.atlas.get(String::from(kitchen::GOLDRING)).is_none());
+    }
+
+    #[test]
+    fn test_sink() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("go north");
+        let result = game.step("put ring in sink");
+        assert!(result.output.contains("You're not carrying the ring."));
+        assert_eq!(
+            game.atlas.loc_of(String::from(kitchen::GOLDRING)),
+            Some(String::from(kitchen::BREAD))
+        );
+        assert_eq!(result.status.score, 0);
+
+        // The sink knows the ring by its real name, whatever the player calls it.
+        game.atlas.move_inventory(String::from(kitchen::GOLDRING));
+        let result = game.step("put gold in sink");
+        assert!(result.output.contains("circles the drain"));
+        assert!(game.atlas.get(String::from(kitchen::GOLDRING)).is_none());
+    }
+
+    #[test]
+    fn test_lock_and_key() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("kick leaves");
+        game.step("go north");
+
+        assert!(game
+            .step("unlock breadbox")
+            .output
+            .contains("don't have a key"));
+        assert!(game
+            .step("take bread")
+            .output
+            .contains("The breadbox is closed."));
+        game.atlas.move_inventory(String::from(forest::KEY));
+        game.atlas.move_inventory(String::from(kitchen::KNIFE));
+        assert!(game
+            .step("unlock breadbox with knife")
+            .output
+            .contains("doesn't fit"));
+
+        let result = game.step("open breadbox with key");
+        assert!(result
+            .output
+            .contains("You unlock the breadbox with the key, and open it."));
+        assert!(!result.output.contains("**"));
+        assert!(result.output.contains("A crusty loaf of bread."));
+        assert!(game.step("look").output.contains("A breadbox. It's open."));
+        assert!(game
+            .step("lock breadbox")
+            .output
+            .contains("close the breadbox first"));
+
+        // Once it's open, what's inside is in reach.
+        let result = game.step("take bread");
+        assert_eq!(result.changes[0], Change::Gained(String::from("bread")));
+        game.step("close breadbox");
+        assert!(game
+            .step("lock breadbox")
+            .output
+            .contains("You lock the breadbox"));
+        assert!(game.step("look").output.contains("It's locked."));
+    }
+
+    #[test]
+    fn test_closed_box_out_of_reach() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("go north");
+
+        // Things with their own handlers still can't be got at through the lid.
+        game.atlas
+            .move_into(String::from(forest::KEY), String::from(kitchen::BREADBOX));
+        let result = game.step("take key");
+        assert!(result.output.contains("The breadbox is closed."));
+        assert!(!game.atlas.is_carried(forest::KEY));
+
+        game.atlas.move_inventory(String::from(kitchen::KNIFE));
+        let result = game.step("cut bread with knife");
+        assert!(result.output.contains("The breadbox is closed."));
+        assert!(game.atlas.get(String::from(kitchen::BREAD)).is_some());
+        assert_eq!(
+            game.atlas.loc_of(String::from(kitchen::GOLDRING)),
+            Some(String::from(kitchen::BREAD))
+        );
+        assert!(game
+            .atlas
+            .get_lockable(String::from(kitchen::BREADBOX))
+            .is_some_and(|lock| lock.locked));
+    }
+
+    #[test]
+    fn test_doors() {
+        let mut game = Game::new(crate::world::create_world);
+        let start = game.start();
+        assert!(start
+            .output
+            .contains("A weathered wooden door. It's closed."));
+        assert!(game.step("knock on door").output.contains("Knock, knock."));
+
+        // A closed door opens on the way through, and it's the same door on the other side.
+        let result = game.step("go north");
+        assert!(result.output.contains("(first opening the door)"));
+        assert!(result
+            .output
+            .contains("A weathered wooden door. It's open."));
+        game.step("close door");
+
+        game.atlas.move_inventory(String::from(forest::KEY));
+        game.step("lock door");
+        let result = game.step("exit");
+        assert!(result.output.contains("The door is locked."));
+        assert_eq!(result.status.room, "kitchen");
+
+        game.step("unlock door with key");
+        assert_eq!(game.step("exit").status.room, "forest");
+    }
+
+    #[test]
+    fn test_step() {
+        let mut game = Game::new(crate::world::create_world);
+        let start = game.start();
+        assert!(start.output.contains("FOREST"));
+        assert!(start.changes.is_empty());
+
+        let result = game.step("kick leaves");
+        assert_eq!(
+            result.changes,
+            vec![Change::Appeared(String::from("key")), Change::Score(5)]
+        );
+        let result = game.step("take key");
+        assert!(result.output.contains("You take the key."));
+        assert_eq!(
+            result.changes,
+            vec![
+                Change::Gained(String::from("key")),
+                Change::Vanished(String::from("key"))
+            ]
+        );
+        assert_eq!(result.status.turns, 2);
+
+        let result = game.step("go north");
+        assert_eq!(
+            result.changes[0],
+            Change::Moved(String::from("forest"), String::from("kitchen"))
+        );
+        assert!(!result.ended);
+
+        let result = game.step_back();
+        assert_eq!(result.status.room, "forest");
+        assert!(game.step("quit").ended);
+    }
+
+    #[test]
+    fn test_brief_shows_portable_objects() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("kick leaves");
+        game.step("go north");
+        let result = game.step("exit");
+        assert!(result.output.contains("FOREST"));
+        assert!(result.output.contains("A shiny key glints in the grass."));
+        assert!(!result.output.contains("There's a pile of leaves here."));
+        assert!(!result.output.contains("You find yourself standing"));
+    }
+
+    #[test]
+    fn test_help_takes_only_what_is_not_carried() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("kick leaves");
+        assert!(game
+            .step("help")
+            .output
+            .contains("TAKE the key, the leaves"));
+        game.step("take key");
+        assert!(game.step("help").output.contains("TAKE the leaves\n"));
+    }
+
+    #[test]
+    fn test_cut_carried_bread() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("go north");
+        game.atlas.move_inventory(String::from(forest::KEY));
+        game.atlas.move_inventory(String::from(kitchen::KNIFE));
+        game.step("open breadbox with key");
+        game.step("take bread");
+        assert!(game
+            .step("cut bread with knife")
+            .output
+            .contains("clean in two"));
+        assert_eq!(
+            game.status().inventory,
+            ["half#1", "half#2", "key", "knife"]
+        );
+
+        // Both halves go by their kind, once.
+        let help = game.step("help").output;
+        assert!(help.contains("the half"));
+        assert!(!help.contains('#'));
+    }
+
+    #[test]
+    fn test_fight_with_weapon_in_hand() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        let result = game.step("attack raccoon with knife");
+        assert!(result.output.contains("You don't have the knife."));
+        assert!(!game
+            .atlas
+            .get_health(String::from("raccoon"))
+            .unwrap()
+            .is_wounded());
+
+        // Cutting the bread takes the knife in hand, too.
+        game.step("go north");
+        let result = game.step("cut bread with knife");
+        assert!(result.output.contains("You don't have the knife."));
+    }
+
+    #[test]
+    fn test_fight_within_reach() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("go north");
+        let result = game.step("attack raccoon");
+        assert!(result.output.contains("You don't see any raccoon here."));
+        assert!(!game
+            .atlas
+            .get_health(String::from(forest::RACCOON))
+            .unwrap()
+            .is_wounded());
+    }
+
+    #[test]
+    fn test_kill_creature() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        // Knocked out, there's no missing it, and any blow will do.
+        let raccoon = String::from(forest::RACCOON);
+        let mut health = game.atlas.get_health(raccoon.clone()).unwrap();
+        health.wound(5);
+        game.atlas.set_health(raccoon.clone(), health);
+
+        let result = game.step("attack raccoon");
+        assert!(result.output.contains("You monster."));
+        assert!(!game.atlas.get_health(raccoon.clone()).unwrap().is_alive());
+        assert_eq!(game.atlas.loc_of(raccoon), Some(String::from(NOWHERE)));
+        assert!(!game.step("look").output.contains("raccoon"));
+        let result = game.step("attack raccoon");
+        assert!(result.output.contains("You don't see any raccoon here."));
+    }
+
+    #[test]
+    fn test_travel() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        let result = game.step("go to kitchen");
+        assert!(result
+            .output
+            .contains("You don't know the way to the kitchen."));
+        let result = game.step("go to the cellar");
+        assert!(result.output.contains("I don't know which way cellar is."));
+
+        game.step("go north");
+        game.step("exit");
+        game.step("go east");
+        let result = game.step("go to kitchen");
+        assert_eq!(result.status.room, "forest");
+        assert!(result.output.contains("You stop. You are not alone."));
+
+        let result = game.step("go to garden");
+        assert_eq!(result.status.room, "garden");
+
+        // With the raccoon knocked out, the whole way is walked.
+        let raccoon = String::from("raccoon");
+        let mut health = game.atlas.get_health(raccoon.clone()).unwrap();
+        health.wound(5);
+        game.atlas.set_health(raccoon, health);
+        let result = game.step("go to kitchen");
+        assert_eq!(result.status.room, "kitchen");
+        assert!(result.output.contains("FOREST"));
+    }
+
+    #[test]
+    fn test_verbosity() {
+        let kitchen = "You are in a kitchen.";
+        for (mode, first, again) in [
+            ("verbose", true, true),
+            ("brief", true, false),
+            ("superbrief", false, false),
+        ] {
+            let mut game = Game::new(crate::world::create_world);
+            game.start();
+            game.step(mode);
+            let result = game.step("go north");
+            assert!(result.output.contains("KITCHEN"), "{}", mode);
+            assert_eq!(result.output.contains(kitchen), first, "{}", mode);
+            game.step("exit");
+            let result = game.step("go north");
+            assert!(result.output.contains("KITCHEN"), "{}", mode);
+            assert_eq!(result.output.contains(kitchen), again, "{}", mode);
+        }
+    }
+
+    #[test]
+    fn test_save_and_restore() {
+        let name = "zork_test_save_and_restore";
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.step("kick leaves");
+        game.step("take key");
+        game.step("go north");
+        let saved = game.status();
+        assert!(game
+            .step(&format!("save {}", name))
+            .output
+            .contains("Saved"));
+
+        game.step("exit");
+        game.step("drop key");
+        assert_ne!(game.status(), saved);
+        let result = game.step(&format!("restore {}", name));
+        assert!(result.output.contains("Restored"));
+        assert_eq!(game.status().room, saved.room);
+        assert_eq!(game.status().inventory, saved.inventory);
+        assert_eq!(game.status().score, saved.score);
+        let _ = fs::remove_file(Game::save_path(Some(name.to_string())).unwrap());
+    }
+
+    #[test]
+    fn test_remote_writes_no_files() {
+        let mut game = Game::new(crate::world::create_world);
+        game.set_remote(true);
+        game.start();
+        for line in ["save", "restore", "script", "script html"] {
+            let result = game.step(line);
+            assert!(result
+                .output
+                .contains("only for games played on this computer"));
+        }
+        assert!(!output::is_recording());
+    }
+
+    #[test]
+    fn test_meta_commands_take_no_time() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        for line in [
+            "score", "version", "hint", "verbs", "help", "xyzzy", "go nrth",
+        ] {
+            assert_eq!(game.step(line).status.turns, 0, "{}", line);
+        }
+        assert_eq!(game.step("wait").status.turns, 1);
+    }
+
+    #[test]
+    fn test_save_path() {
+        assert_eq!(
+            Game::save_path(None),
+            Some(format!("{}/{}", SAVE_DIR, SAVE_FILE))
+        );
+        assert_eq!(
+            Game::save_path(Some(String::from("mygame"))),
+            Some(format!("{}/mygame.sav", SAVE_DIR))
+        );
+        assert_eq!(
+            Game::save_path(Some(String::from("mygame.txt"))),
+            Some(format!("{}/mygame.txt", SAVE_DIR))
+        );
+        for name in ["/etc/passwd", "../zork.sav", "..", "saves/../../x", ""] {
+            assert_eq!(Game::save_path(Some(String::from(name))), None, "{}", name);
+        }
+
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        let result = game.step("save /tmp/zork.sav");
+        assert!(result.output.contains("A saved game needs a plain name"));
+    }
+
+    #[test]
+    fn test_same_seed_same_game() {
+        let commands = [
+            "wait",
+            "attack raccoon",
+            "wait",
+            "attack raccoon",
+            "go north",
+        ];
+        let play = |seed: u64| -> Vec<String> {
+            let mut game = Game::new(crate::world::create_world);
+            game.set_seed(seed);
+            game.start();
+            commands.iter().map(|c| game.step(c).output).collect()
+        };
+        assert_eq!(play(42), play(42));
+        assert_eq!(play(7), play(7));
+    }
+
+    /// Carried along, it won't let the player leave, and it makes them listen while they wait.
+    struct Anchor;
+
+    impl GameObject for Anchor {
+        fn name(&self) -> String {
+            String::from("anchor")
+        }
+
+        fn loc(&self) -> String {
+            String::from(INVENTORY)
+        }
+
+        fn before(&mut self, action: &Action) -> Notify {
+            match action {
+                Action::Go(_) => {
+                    outln!("The anchor holds you fast.");
+                    Notify::Handled
+                }
+                Action::Wait => Notify::Instead(Action::Listen(Some(String::from("nothing")))),
+                _ => Notify::Unhandled,
+            }
+        }
+    }
+
+    #[test]
+    fn test_before_every_action() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        game.atlas.add(Box::new(Anchor));
+
+        let result = game.step("go north");
+        assert!(result.output.contains("The anchor holds you fast."));
+        assert_eq!(result.status.room, "forest");
+        let result = game.step("wait");
+        assert_eq!(result.output.matches("Nothing happens.").count(), 1);
+        // Asking about the game isn't doing anything in it.
+        assert!(game.step("score").output.contains("Your score is"));
+    }
+
+    type Log = Rc<RefCell<Vec<(String, Event)>>>;
+
+    /// Writes down every event, in the order it's told them. It may drop whatever is taken, straight away.
+    struct Watcher {
+        name: String,
+        log: Log,
+        drops: bool,
+    }
+
+    impl GameObject for Watcher {
+        fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        fn observes(&self, _event: &Event) -> bool {
+            true
+        }
+
+        fn observe(&mut self, event: Event) -> Notify {
+            self.log
+                .borrow_mut()
+                .push((self.name.clone(), event.clone()));
+            match event {
+                Event::Taken(_, object) if self.drops => Notify::Move(object, Location::Local),
+                _ => Notify::Unhandled,
+            }
+        }
+    }
+
+    fn watch(game: &mut Game, name: &str, drops: bool, log: &Log) {
+        game.atlas.add(Box::new(Watcher {
+            name: name.to_string(),
+            log: log.clone(),
+            drops,
+        }));
+    }
+
+    #[test]
+    fn test_events() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        let log = Log::default();
+        watch(&mut game, "watcher", false, &log);
+        let player = String::new();
+        let events =
+            |log: &Log| -> Vec<Event> { log.borrow_mut().drain(..).map(|(_, e)| e).collect() };
+
+        game.step("kick leaves");
+        assert_eq!(
+            events(&log),
+            vec![
+                Event::ScoreChanged(player.clone(), 5, 5),
+                Event::TurnEnded(player.clone(), 1)
+            ]
+        );
+        game.step("take key");
+        assert_eq!(
+            events(&log)[0],
+            Event::Taken(player.clone(), String::from("key"))
+        );
+        game.step("drop key");
+        assert_eq!(
+            events(&log)[0],
+            Event::Dropped(player.clone(), String::from("key"))
+        );
+        game.step("go north");
+        let seen = events(&log);
+        assert!(seen.contains(&Event::Left(player.clone(), String::from("forest"))));
+        assert!(seen.contains(&Event::Arrived(player.clone(), String::from("kitchen"))));
+    }
+
+    #[test]
+    fn test_events_queued() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        let log = Log::default();
+        watch(&mut game, "a watcher", true, &log);
+        watch(&mut game, "b watcher", false, &log);
+        game.step("kick leaves");
+        log.borrow_mut().clear();
+
+        // The key is dropped while the take is still being told. Everyone hears of the take first.
+        game.step("take key");
+        let (player, key) = (String::new(), String::from("key"));
+        let seen: Vec<(String, Event)> = log.borrow_mut().drain(..3).collect();
+        assert_eq!(
+            seen,
+            vec![
+                (
+                    String::from("a watcher"),
+                    Event::Taken(player.clone(), key.clone())
+                ),
+                (
+                    String::from("b watcher"),
+                    Event::Taken(player.clone(), key.clone())
+                ),
+                (
+                    String::from("a watcher"),
+                    Event::Dropped(player.clone(), key.clone())
+                ),
+            ]
+        );
+        assert!(!game.atlas.is_carried("key"));
+    }
+
+    #[test]
+    fn test_lose_points() {
+        let mut game = Game::new(crate::world::create_world);
+        game.start();
+        let effects = vec![crate::game::Effect::Score(-3)];
+        game.atlas
+            .notify(String::from("forest"), Notify::Effects(effects));
+        let (eventful, text) = output::capture(|| game.end_turn());
+        assert!(eventful);
+        assert!(text.contains("[Your score has gone down by 3 points.]"));
+        assert_eq!(game.score, -3);
+    }
 }
@@ -0,0 +1,43 @@
+/// Lock properties. Anything that opens and closes has one: doors, chests, the breadbox.
+/// Only the keys listed fit the lock. With no keys, it opens and closes, but can't be locked.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lockable {
+    pub open: bool,
+    pub locked: bool,      // only when closed
+    pub keys: Vec<String>, // names of the objects that fit the lock
+}
+
+impl Lockable {
+    /// Something that opens and closes, without a lock.
+    pub fn new(open: bool) -> Self {
+        Self {
+            open,
+            ..Self::default()
+        }
+    }
+
+    /// Something closed and locked, that these keys will open.
+    pub fn locked(keys: &[&str]) -> Self {
+        Self {
+            open: false,
+            locked: true,
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// Does it have a lock at all?
+    pub fn has_lock(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// How it looks: "open", "closed" or "locked".
+    pub fn state(&self) -> &'static str {
+        if self.open {
+            "open"
+        } else if self.locked {
+            "locked"
+        } else {
+            "closed"
+        }
+    }
+}
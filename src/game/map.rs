@@ -0,0 +1,335 @@
+use super::{Direction, GameAtlas};
+use std::collections::{HashMap, VecDeque};
+
+/// How far from the player the MAP command will draw.
+pub static MAP_RADIUS: i32 = 3;
+
+/// A link between two rooms. The player travelled `dir` from `from` and arrived at `to`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    pub from: String,
+    pub dir: Direction,
+    pub to: String,
+}
+
+/// The automap remembers every room visited and the directions travelled between them.
+#[derive(Default)]
+pub struct Automap {
+    rooms: Vec<String>, // in order of discovery
+    links: Vec<Link>,
+}
+
+/// Grid offset for a compass direction. Other directions don't have a place on paper.
+fn offset(dir: &Direction) -> Option<(i32, i32)> {
+    match dir {
+        Direction::North => Some((0, -1)),
+        Direction::South => Some((0, 1)),
+        Direction::East => Some((1, 0)),
+        Direction::West => Some((-1, 0)),
+        _ => None,
+    }
+}
+
+impl Automap {
+    /// Build the map of the whole world from the exits declared by each room. (For authors.)
+    pub fn from_world(atlas: &GameAtlas) -> Self {
+        let mut map = Self::default();
+        map.visit(atlas.here());
+        for (room, exits) in atlas.get_exits() {
+            map.visit(room.clone());
+            for (dir, to) in exits {
+                map.visit(to.clone());
+                map.link(room.clone(), dir, to);
+            }
+        }
+        map
+    }
+
+    pub fn is_visited(&self, room: &str) -> bool {
+        self.rooms.iter().any(|r| r == room)
+    }
+
+    /// Remember a room.
+    pub fn visit(&mut self, room: String) {
+        if !self.is_visited(&room) {
+            self.rooms.push(room);
+        }
+    }
+
+    /// Learn the declared exits between rooms already visited, so the way back is known too.
+    pub fn learn(&mut self, exits: Vec<(String, Vec<(Direction, String)>)>) {
+        for (room, exits) in exits {
+            for (dir, to) in exits {
+                if self.is_visited(&room) && self.is_visited(&to) {
+                    self.link(room.clone(), dir, to);
+                }
+            }
+        }
+    }
+
+    /// Remember the way from one room to another.
+    pub fn link(&mut self, from: String, dir: Direction, to: String) {
+        let link = Link { from, dir, to };
+        if !self.links.contains(&link) {
+            self.links.push(link);
+        }
+    }
+
+    /// Find the shortest known way from one room to another.
+    /// Returns each step as the direction to go and the room it should lead to.
+    pub fn path(&self, from: &str, to: &str) -> Option<Vec<(Direction, String)>> {
+        let mut came_from: HashMap<String, &Link> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::from([from]);
+        while let Some(room) = queue.pop_front() {
+            if room == to {
+                let mut steps = Vec::new();
+                let mut at = to;
+                while let Some(link) = came_from.get(at) {
+                    steps.push((link.dir.clone(), link.to.clone()));
+                    at = &link.from;
+                }
+                steps.reverse();
+                return Some(steps);
+            }
+            for link in self.links.iter().filter(|l| l.from == room) {
+                if link.to != from && !came_from.contains_key(&link.to) {
+                    came_from.insert(link.to.clone(), link);
+                    queue.push_back(&link.to);
+                }
+            }
+        }
+        None
+    }
+
+    /// Lay out the rooms on a grid, starting from `here`.
+    /// Compass links place rooms first. Rooms reached only by other directions (up, in, out)
+    /// are placed in the first free cell beside the room they connect to.
+    fn layout(&self, here: &str) -> HashMap<String, (i32, i32)> {
+        let mut grid: HashMap<String, (i32, i32)> = HashMap::new();
+        if !self.is_visited(here) {
+            return grid;
+        }
+        grid.insert(here.to_string(), (0, 0));
+
+        for compass in [true, false] {
+            let mut queue: VecDeque<String> = grid.keys().cloned().collect();
+            while let Some(room) = queue.pop_front() {
+                let (x, y) = grid[&room];
+                for link in self.links.iter() {
+                    // Links work both ways on paper.
+                    let (next, step) = if link.from == room {
+                        (link.to.clone(), offset(&link.dir))
+                    } else if link.to == room {
+                        (
+                            link.from.clone(),
+                            offset(&link.dir).map(|(dx, dy)| (-dx, -dy)),
+                        )
+                    } else {
+                        continue;
+                    };
+                    if grid.contains_key(&next) {
+                        continue;
+                    }
+                    let cell = match step {
+                        Some((dx, dy)) => Some((x + dx, y + dy)),
+                        None if compass => None,
+                        None => [(1, 0), (0, 1), (-1, 0), (0, -1)]
+                            .iter()
+                            .map(|(dx, dy)| (x + dx, y + dy))
+                            .find(|c| !grid.values().any(|v| v == c)),
+                    };
+                    if let Some(cell) = cell.filter(|c| !grid.values().any(|v| v == c)) {
+                        grid.insert(next.clone(), cell);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        grid
+    }
+
+    /// Draw the map around `here` as ASCII art. Only rooms within `radius` steps are drawn.
+    /// Compass connections are drawn with `---` and `|`, all others with `...` and `:`.
+    pub fn render(&self, here: &str, radius: Option<i32>) -> String {
+        let grid: HashMap<String, (i32, i32)> = self
+            .layout(here)
+            .into_iter()
+            .filter(|(_, (x, y))| !radius.is_some_and(|r| x.abs() > r || y.abs() > r))
+            .collect();
+        if grid.is_empty() {
+            return String::from("You haven't been anywhere yet.\n");
+        }
+
+        let label = |room: &str| {
+            if room == here {
+                format!("[*{}*]", room)
+            } else {
+                format!("[ {} ]", room)
+            }
+        };
+        let width = grid.keys().map(|r| label(r).len()).max().unwrap();
+        let at = |x: i32, y: i32| grid.iter().find(|(_, c)| **c == (x, y)).map(|(r, _)| r);
+        let joint = |a: &String, b: &String, compass: bool| {
+            self.links.iter().any(|l| {
+                ((l.from == *a && l.to == *b) || (l.from == *b && l.to == *a))
+                    && offset(&l.dir).is_some() == compass
+            })
+        };
+
+        let min_x = grid.values().map(|c| c.0).min().unwrap();
+        let max_x = grid.values().map(|c| c.0).max().unwrap();
+        let min_y = grid.values().map(|c| c.1).min().unwrap();
+        let max_y = grid.values().map(|c| c.1).max().unwrap();
+
+        let mut out = String::new();
+        for y in min_y..=max_y {
+            // Rooms and east-west connections.
+            let mut line = String::new();
+            for x in min_x..=max_x {
+                match at(x, y) {
+                    Some(room) => line.push_str(&format!("{:^width$}", label(room))),
+                    None => line.push_str(&" ".repeat(width)),
+                }
+                if x < max_x {
+                    line.push_str(match (at(x, y), at(x + 1, y)) {
+                        (Some(a), Some(b)) if joint(a, b, true) => "---",
+                        (Some(a), Some(b)) if joint(a, b, false) => "...",
+                        _ => "   ",
+                    });
+                }
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+
+            // North-south connections.
+            if y < max_y {
+                let mut line = String::new();
+                for x in min_x..=max_x {
+                    let c = match (at(x, y), at(x, y + 1)) {
+                        (Some(a), Some(b)) if joint(a, b, true) => "|",
+                        (Some(a), Some(b)) if joint(a, b, false) => ":",
+                        _ => " ",
+                    };
+                    line.push_str(&format!("{:^width$}   ", c));
+                }
+                out.push_str(line.trim_end());
+                out.push('\n');
+            }
+        }
+
+        // Directions that can't be drawn on paper.
+        for link in self.links.iter().filter(|l| offset(&l.dir).is_none()) {
+            if grid.contains_key(&link.from) && grid.contains_key(&link.to) {
+                out.push_str(&format!(
+                    "From the {}, {} leads to the {}.\n",
+                    link.from,
+                    format!("{:?}", link.dir).to_uppercase(),
+                    link.to
+                ));
+            }
+        }
+        if radius.is_none() {
+            let unplaced: Vec<String> = self
+                .rooms
+                .iter()
+                .filter(|r| !grid.contains_key(*r))
+                .cloned()
+                .collect();
+            if !unplaced.is_empty() {
+                out.push_str(&format!("Not shown: {}.\n", unplaced.join(", ")));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_compass() {
+        let mut map = Automap::default();
+        map.visit(String::from("forest"));
+        map.visit(String::from("kitchen"));
+        map.link(
+            String::from("forest"),
+            Direction::North,
+            String::from("kitchen"),
+        );
+
+        let text = map.render("forest", Some(MAP_RADIUS));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("[ kitchen ]"));
+        assert_eq!(lines[1].trim(), "|");
+        assert!(lines[2].contains("[*forest*]"));
+    }
+
+    #[test]
+    fn test_render_other_directions() {
+        let mut map = Automap::default();
+        map.visit(String::from("house"));
+        map.visit(String::from("cellar"));
+        map.link(
+            String::from("house"),
+            Direction::Down,
+            String::from("cellar"),
+        );
+
+        let text = map.render("cellar", None);
+        assert!(text.contains("[ house ]...[*cellar*]") || text.contains("[*cellar*]...[ house ]"));
+        assert!(text.contains("From the house, DOWN leads to the cellar."));
+    }
+
+    #[test]
+    fn test_path() {
+        let mut map = Automap::default();
+        map.link(
+            String::from("forest"),
+            Direction::North,
+            String::from("kitchen"),
+        );
+        map.link(
+            String::from("kitchen"),
+            Direction::Up,
+            String::from("attic"),
+        );
+        map.link(
+            String::from("attic"),
+            Direction::Down,
+            String::from("kitchen"),
+        );
+        map.link(
+            String::from("kitchen"),
+            Direction::Exit,
+            String::from("forest"),
+        );
+
+        assert_eq!(
+            map.path("forest", "attic"),
+            Some(vec![
+                (Direction::North, String::from("kitchen")),
+                (Direction::Up, String::from("attic")),
+            ])
+        );
+        assert_eq!(
+            map.path("attic", "forest"),
+            Some(vec![
+                (Direction::Down, String::from("kitchen")),
+                (Direction::Exit, String::from("forest")),
+            ])
+        );
+        assert_eq!(map.path("forest", "forest"), Some(vec![]));
+        assert_eq!(map.path("forest", "cellar"), None);
+    }
+
+    #[test]
+    fn test_render_nowhere() {
+        let map = Automap::default();
+        assert_eq!(
+            map.render("forest", None),
+            "You haven't been anywhere yet.\n"
+        );
+    }
+}
@@ -1,19 +1,43 @@
-pub mod atlas;
-pub mod context;
-pub mod game;
-pub mod objects;
-pub mod parser;
-pub mod title;
-
-pub use atlas::{GameAtlas, INVENTORY, NOWHERE};
+#[macro_use]
+pub mod output;
+pub(crate) mod atlas;
+pub(crate) mod combat;
+pub(crate) mod container;
+pub(crate) mod context;
+pub(crate) mod defaults;
+#[allow(clippy::module_inception)]
+pub(crate) mod game;
+pub(crate) mod lock;
+pub(crate) mod map;
+pub(crate) mod parser;
+pub(crate) mod puzzles;
+pub(crate) mod replay;
+pub(crate) mod shared;
+pub(crate) mod title;
+pub(crate) mod transcript;
+
+pub use atlas::{kind_of, GameAtlas, INVENTORY, NOWHERE};
+pub use combat::{Health, Weapon};
+pub use container::Container;
 pub use context::GameContext;
-pub use game::Game;
-pub use objects::forest::{Forest, Key, Leaves};
-pub use objects::kitchen::{Bread, BreadBox, Kitchen, Knife, Sink};
+pub use game::{Change, Game, Status, TurnResult};
+pub use lock::Lockable;
 pub use parser::Token;
+pub use puzzles::{Puzzle, Puzzles};
+
+use crate::mini::{MiniGame, Outcome};
 
 pub type Handled = bool;
 
+/// Builds a new world, with its puzzles. (See world::create_world.)
+pub type World = fn() -> (GameAtlas, Vec<Puzzle>);
+
+/// Makes a new object of some kind, with the given unique name. (See GameAtlas::register.)
+pub type Factory = fn(String) -> Box<dyn GameObject>;
+
+/// The random number generator used by everything in the game. (See GameAtlas::rng.)
+pub type GameRng = rand::rngs::StdRng;
+
 #[derive(Clone, Debug, PartialEq)]
 
 pub enum Direction {
@@ -27,19 +51,38 @@ pub enum Direction {
     Enter,
 }
 
+impl Direction {
+    /// All directions, in the order they are listed to the player.
+    pub fn all() -> Vec<Direction> {
+        vec![
+            Direction::North,
+            Direction::South,
+            Direction::East,
+            Direction::West,
+            Direction::Up,
+            Direction::Down,
+            Direction::Enter,
+            Direction::Exit,
+        ]
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
-#[allow(dead_code)]
+#[allow(dead_code, clippy::enum_variant_names)]
 // Actions are created by the parser.
 pub enum Action {
     Go(Direction), // handled by "here" object, which calls SetLocation on mediator if successful.
+    Travel(String), // walk to a room the player has visited before, one Go at a time.
 
     // Actions that use "here" object if not specified.
     Climb(Option<String>),
     Describe(Option<String>),
     Examine(Option<String>),
     Follow(Option<String>),
+    Knock(Option<String>),
     Listen(Option<String>),
     Take(Option<String>),
+    Wear(Option<String>),
 
     // Actions with optional indirect object.
     // If Indirect object is not specified, the game will choose one.
@@ -47,22 +90,38 @@ pub enum Action {
     Drop(String, Option<String>),
     Light(String, Option<String>),
     Open(String, Option<String>),
+    Close(String, Option<String>),
+    Lock(String, Option<String>),   // what, with which key
+    Unlock(String, Option<String>), // ditto
     Read(String, Option<String>),
-    Say(String, Option<String>), // say something to an object
+    Press(String, Option<String>), // press keys or buttons: what, on what
+    Say(String, Option<String>),   // say something to an object
     Use(String, Option<String>),
 
     Die,
+    Diagnose,
     Help,
+    Hint,
     Inventory,
+    Map,
+    Score,
+    Guess,
+    Version,
+    Save(Option<String>),    // save to the named file, or the default
+    Restore(Option<String>), // restore from the named file, or the default
+    Verbs,
+    Verbose,
+    Brief,
+    Superbrief,
+    Script(Option<String>), // start recording a transcript, in the given format
+    Unscript,
     Wait,
     Quit,
 
-    // TDOO: get hints from the game.
-    // Hint,
-
     // These events are sent when player moves between locations. (Not commands.)
     Arrive(String),
     Leave(String),
+    Played(String, Outcome), // a mini game you started is over: name, outcome
 
     // Error actions handled by game.
     UnknownAction(String),
@@ -76,6 +135,35 @@ pub enum Action {
 
 #[allow(dead_code)]
 impl Action {
+    /// Every player action that can be done to an object, in the order they are listed in help.
+    pub fn all_for(object: &str) -> Vec<Action> {
+        let o = object.to_string();
+        vec![
+            Action::Examine(Some(o.clone())),
+            Action::Take(Some(o.clone())),
+            Action::Drop(o.clone(), None),
+            Action::Open(o.clone(), None),
+            Action::Close(o.clone(), None),
+            Action::Lock(o.clone(), None),
+            Action::Unlock(o.clone(), None),
+            Action::Read(o.clone(), None),
+            Action::Use(o.clone(), None),
+            Action::Wear(Some(o.clone())),
+            Action::Attack(o.clone(), None),
+            Action::Light(o.clone(), None),
+            Action::Climb(Some(o.clone())),
+            Action::Follow(Some(o.clone())),
+            Action::Knock(Some(o.clone())),
+            Action::Listen(Some(o.clone())),
+            Action::Say(o, None),
+        ]
+    }
+
+    /// The verb for this action, as the player would type it.
+    pub fn verb(&self) -> String {
+        self.unpack_action().0
+    }
+
     pub fn get_object_or_here(&self, here: &str) -> String {
         match self.get_object() {
             Some(obj) => obj,
@@ -96,7 +184,11 @@ impl Action {
             | Action::Drop(obj, _)
             | Action::Light(obj, _)
             | Action::Open(obj, _)
+            | Action::Close(obj, _)
+            | Action::Lock(obj, _)
+            | Action::Unlock(obj, _)
             | Action::Read(obj, _)
+            | Action::Press(obj, _)
             | Action::Say(obj, _)
             | Action::Use(obj, _) => {
                 if obj.as_str() != "" {
@@ -109,8 +201,10 @@ impl Action {
             | Action::Climb(obj)
             | Action::Examine(obj)
             | Action::Follow(obj)
+            | Action::Knock(obj)
             | Action::Listen(obj)
-            | Action::Take(obj) => obj.clone(),
+            | Action::Take(obj)
+            | Action::Wear(obj) => obj.clone(),
             _ => None,
         }
     }
@@ -121,15 +215,21 @@ impl Action {
             Action::Drop(_, obj) => Action::Drop(prso, obj.clone()),
             Action::Light(_, obj) => Action::Light(prso, obj.clone()),
             Action::Open(_, obj) => Action::Open(prso, obj.clone()),
+            Action::Close(_, obj) => Action::Close(prso, obj.clone()),
+            Action::Lock(_, obj) => Action::Lock(prso, obj.clone()),
+            Action::Unlock(_, obj) => Action::Unlock(prso, obj.clone()),
             Action::Read(_, obj) => Action::Read(prso, obj.clone()),
+            Action::Press(_, obj) => Action::Press(prso, obj.clone()),
             Action::Say(_, obj) => Action::Say(prso, obj.clone()),
             Action::Use(_, obj) => Action::Use(prso, obj.clone()),
             Action::Describe(_) => Action::Describe(Some(prso)),
             Action::Climb(_) => Action::Climb(Some(prso)),
             Action::Examine(_) => Action::Examine(Some(prso)),
             Action::Follow(_) => Action::Follow(Some(prso)),
+            Action::Knock(_) => Action::Knock(Some(prso)),
             Action::Listen(_) => Action::Listen(Some(prso)),
             Action::Take(_) => Action::Take(Some(prso)),
+            Action::Wear(_) => Action::Wear(Some(prso)),
             _ => self.clone(),
         }
     }
@@ -140,7 +240,11 @@ impl Action {
             | Action::Drop(_, obj)
             | Action::Light(_, obj)
             | Action::Open(_, obj)
+            | Action::Close(_, obj)
+            | Action::Lock(_, obj)
+            | Action::Unlock(_, obj)
             | Action::Read(_, obj)
+            | Action::Press(_, obj)
             | Action::Say(_, obj)
             | Action::Use(_, obj) => obj.clone(),
             _ => None,
@@ -153,13 +257,18 @@ impl Action {
             Action::Drop(obj, _) => Action::Drop(obj.clone(), Some(prsi)),
             Action::Light(obj, _) => Action::Light(obj.clone(), Some(prsi)),
             Action::Open(obj, _) => Action::Open(obj.clone(), Some(prsi)),
+            Action::Close(obj, _) => Action::Close(obj.clone(), Some(prsi)),
+            Action::Lock(obj, _) => Action::Lock(obj.clone(), Some(prsi)),
+            Action::Unlock(obj, _) => Action::Unlock(obj.clone(), Some(prsi)),
             Action::Read(obj, _) => Action::Read(obj.clone(), Some(prsi)),
+            Action::Press(obj, _) => Action::Press(obj.clone(), Some(prsi)),
             Action::Say(obj, _) => Action::Say(obj.clone(), Some(prsi)),
             Action::Use(obj, _) => Action::Use(obj.clone(), Some(prsi)),
             _ => self.clone(),
         }
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     fn is_error(&self) -> bool {
         match self {
             Action::UnknownAction(_) => true,
@@ -171,8 +280,21 @@ impl Action {
         }
     }
 
+    /// Is this about the game, rather than something done in the world? Meta commands take no time.
+    #[allow(clippy::match_like_matches_macro)]
+    pub fn is_meta(&self) -> bool {
+        match self {
+            Action::Diagnose | Action::Help | Action::Hint | Action::Map => true,
+            Action::Score | Action::Version | Action::Verbs => true,
+            Action::Verbose | Action::Brief | Action::Superbrief => true,
+            Action::Save(_) | Action::Restore(_) => true,
+            Action::Script(_) | Action::Unscript => true,
+            _ => self.is_error(),
+        }
+    }
+
     fn print(&self) {
-        println!("Action: {:?}", self)
+        outln!("Action: {:?}", self)
     }
 
     fn unpack_action(&self) -> (String, Option<String>, Option<String>) {
@@ -188,29 +310,52 @@ impl Action {
                 Direction::Enter => (String::from("go"), Some("enter".to_string()), None),
             },
 
+            Action::Travel(r) => (String::from("travel"), Some(r.clone()), None),
+
             Action::Climb(o) => (String::from("climb"), o.clone(), None),
             Action::Describe(o) => (String::from("describe"), o.clone(), None),
             Action::Examine(o) => (String::from("examine"), o.clone(), None),
             Action::Follow(o) => (String::from("follow"), o.clone(), None),
+            Action::Knock(o) => (String::from("knock"), o.clone(), None),
             Action::Listen(o) => (String::from("listen"), o.clone(), None),
             Action::Take(o) => (String::from("take"), o.clone(), None),
+            Action::Wear(o) => (String::from("wear"), o.clone(), None),
 
             Action::Attack(o, i) => (String::from("attack"), Some(o.clone()), i.clone()),
             Action::Drop(o, i) => (String::from("drop"), Some(o.clone()), i.clone()),
             Action::Light(o, i) => (String::from("light"), Some(o.clone()), i.clone()),
             Action::Open(o, i) => (String::from("open"), Some(o.clone()), i.clone()),
+            Action::Close(o, i) => (String::from("close"), Some(o.clone()), i.clone()),
+            Action::Lock(o, i) => (String::from("lock"), Some(o.clone()), i.clone()),
+            Action::Unlock(o, i) => (String::from("unlock"), Some(o.clone()), i.clone()),
             Action::Read(o, i) => (String::from("read"), Some(o.clone()), i.clone()),
+            Action::Press(o, i) => (String::from("press"), Some(o.clone()), i.clone()),
             Action::Say(o, i) => (String::from("say"), Some(o.clone()), i.clone()),
             Action::Use(o, i) => (String::from("use"), Some(o.clone()), i.clone()),
 
             Action::Die => (String::from("die"), None, None),
+            Action::Diagnose => (String::from("diagnose"), None, None),
             Action::Help => (String::from("help"), None, None),
+            Action::Hint => (String::from("hint"), None, None),
             Action::Inventory => (String::from("inventory"), None, None),
+            Action::Map => (String::from("map"), None, None),
+            Action::Score => (String::from("score"), None, None),
+            Action::Guess => (String::from("guess"), None, None),
+            Action::Version => (String::from("version"), None, None),
+            Action::Save(f) => (String::from("save"), f.clone(), None),
+            Action::Restore(f) => (String::from("restore"), f.clone(), None),
+            Action::Verbs => (String::from("verbs"), None, None),
+            Action::Verbose => (String::from("verbose"), None, None),
+            Action::Brief => (String::from("brief"), None, None),
+            Action::Superbrief => (String::from("superbrief"), None, None),
+            Action::Script(f) => (String::from("script"), f.clone(), None),
+            Action::Unscript => (String::from("unscript"), None, None),
             Action::Wait => (String::from("wait"), None, None),
             Action::Quit => (String::from("quit"), None, None),
 
             Action::Arrive(o) => (String::from("arrive"), Some(o.clone()), None),
             Action::Leave(o) => (String::from("leave"), Some(o.clone()), None),
+            Action::Played(o, _) => (String::from("played"), Some(o.clone()), None),
 
             Action::AmbiguousObject(v) => (
                 String::from("ambiguousObj"),
@@ -227,6 +372,18 @@ impl Action {
     }
 }
 
+/// Something that happened in the world. Any object can watch for events, wherever it is.
+/// Every event starts with the name of the player it happened to. (See GameObject::observes.)
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Arrived(String, String),        // player, the room they arrived in
+    Left(String, String),           // player, the room they left
+    Taken(String, String),          // player, the object they took
+    Dropped(String, String),        // player, the object they dropped
+    TurnEnded(String, u32),         // player, turns they've played so far
+    ScoreChanged(String, i32, i32), // player, points gained (or lost), their new score
+}
+
 pub enum Location {
     Local,
     Inventory,
@@ -239,6 +396,24 @@ pub enum Notify {
     Set(Location),           // update game location
     Move(String, Location),  // object name, new location
     Replace(String, String), // old object name, new object name, same location
+    Play(Box<dyn MiniGame>), // start a mini game, and get Action::Played when it's over
+    Effects(Vec<Effect>),    // do all of these, in order, or none of them
+    Instead(Action),         // play this action instead (See GameObject::before.)
+}
+
+/// One thing an action does to the world. An action can do several. (See Notify::Effects.)
+/// ! Effects are only checked for the objects they name, not for whether each one will work.
+pub enum Effect {
+    Move(String, Location),        // object name, new location
+    Replace(String, String),       // old object name, new object name, same location
+    Create(Box<dyn GameObject>),   // add a new object to the world
+    Spawn(String, Location),       // make a new object from a registered prototype: kind, where
+    Destroy(String),               // take an object out of the world for good
+    SetRoom(String),               // move the player to the room
+    SetFlag(String, String, bool), // object name, flag, value
+    Score(i32),                    // award points, or take them away
+    Schedule(u32, String, Action), // in so many turns, send the action to the object
+    Print(String),                 // say something to the player
 }
 
 #[allow(unused_variables)]
@@ -263,4 +438,92 @@ pub trait GameObject {
     fn act(&mut self, action: Action) -> Notify {
         Notify::Unhandled
     }
+
+    /// Intercept an action before anything handles it. Only the room and what the player carries are asked.
+    /// Return Handled to stop it, or Instead to play another action. Default is to let it through.
+    fn before(&mut self, action: &Action) -> Notify {
+        Notify::Unhandled
+    }
+
+    /// React to an action once it's been handled. Only the room and what the player carries are asked.
+    /// Default is no reaction.
+    fn after(&mut self, action: &Action) -> Notify {
+        Notify::Unhandled
+    }
+
+    /// Does this object want to hear about the event? Default is no.
+    fn observes(&self, event: &Event) -> bool {
+        false
+    }
+
+    /// React to an event. Default is to do nothing.
+    fn observe(&mut self, event: Event) -> Notify {
+        Notify::Unhandled
+    }
+
+    /// Can the player pick this object up and put it down? The game does it when the object doesn't. Default is no.
+    fn portable(&self) -> bool {
+        false
+    }
+
+    /// Should the game answer for this object when nothing handles the action? Default is yes.
+    /// (See defaults::response.)
+    fn has_default(&self, action: &Action) -> bool {
+        true
+    }
+
+    /// Get the rooms this object joins, if it's a door. It's in all of them at once. Default is none.
+    fn sides(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Get other names for this object: the gate's keypad is part of the gate. Default is none.
+    fn aliases(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Do something on your own, once per turn, while the player is near. Default is to do nothing.
+    /// Any randomness must come from `rng`, so the game can be replayed.
+    fn tick(&mut self, rng: &mut GameRng) -> Notify {
+        Notify::Unhandled
+    }
+
+    /// Set a flag on this object, e.g. the bread is "sliced". Default is to ignore it.
+    fn set_flag(&mut self, flag: &str, value: bool) {}
+
+    /// Is the flag set on this object? Default is no.
+    fn flag(&self, flag: &str) -> bool {
+        false
+    }
+
+    /// Get the exits from this room, for the map. Default is none.
+    fn exits(&self) -> Vec<(Direction, String)> {
+        Vec::new()
+    }
+
+    /// Get the weapon properties, if this object can be used to fight. Default is None.
+    fn weapon(&self) -> Option<Weapon> {
+        None
+    }
+
+    /// Get the container properties, if things can be put in or on this object. Default is None.
+    fn container(&self) -> Option<Container> {
+        None
+    }
+
+    /// Get the lock properties, if this object opens and closes. Default is None.
+    fn lockable(&self) -> Option<Lockable> {
+        None
+    }
+
+    /// Set the lock properties: opened, closed, locked or unlocked. Default is to do nothing.
+    fn set_lockable(&mut self, lockable: Lockable) {}
+
+    /// Get the health of this creature, if it can be fought. Default is None.
+    fn health(&self) -> Option<Health> {
+        None
+    }
+
+    /// Set the health of this creature. Default is to do nothing.
+    fn set_health(&mut self, health: Health) {}
 }
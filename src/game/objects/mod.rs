@@ -1,2 +0,0 @@
-pub mod forest;
-pub mod kitchen;
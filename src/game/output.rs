@@ -0,0 +1,157 @@
+use super::transcript::Transcript;
+use std::cell::RefCell;
+use std::io::{self, stdout, Write};
+use std::rc::Rc;
+
+/// Where game output goes, instead of stdout. (See set_sink.)
+pub type Sink = Box<dyn Write>;
+
+/// Everything the game says goes through here, so it can be copied into a transcript.
+/// Use the `out!` and `outln!` macros in place of `print!` and `println!`.
+/// ! Output is per thread, so every game running on its own thread has its own transcript.
+#[derive(Default)]
+struct Output {
+    transcript: Option<Transcript>,
+    muted: bool,        // say nothing, e.g. while restoring a saved game
+    sink: Option<Sink>, // somewhere other than stdout, e.g. a network connection
+}
+
+thread_local! {
+    static OUTPUT: RefCell<Output> = RefCell::new(Output::default());
+}
+
+/// Like `print!`, but the text is also recorded in the transcript.
+#[macro_export]
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::output::write(&format!($($arg)*))
+    };
+}
+
+/// Like `println!`, but the text is also recorded in the transcript.
+#[macro_export]
+macro_rules! outln {
+    () => {
+        $crate::output::write("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write(&format!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// Send text to the player's screen.
+fn show(text: &str, flush: bool) {
+    OUTPUT.with(|o| match o.borrow_mut().sink.as_mut() {
+        Some(sink) => {
+            sink.write_all(text.as_bytes()).ok();
+            if flush {
+                sink.flush().ok();
+            }
+        }
+        None => {
+            print!("{}", text);
+            if flush {
+                stdout().flush().ok();
+            }
+        }
+    });
+}
+
+/// Write game output.
+pub fn write(text: &str) {
+    if is_muted() {
+        return;
+    }
+    show(text, false);
+    OUTPUT.with(|o| {
+        if let Some(t) = o.borrow_mut().transcript.as_mut() {
+            t.output(text);
+        }
+    });
+}
+
+/// Write the prompt, and make sure the player can see it before they type.
+pub fn prompt(text: &str) {
+    show(text, true);
+    OUTPUT.with(|o| {
+        if let Some(t) = o.borrow_mut().transcript.as_mut() {
+            t.prompt(text);
+        }
+    });
+}
+
+/// Show a line as if the player typed it, and record it. (Used for replays.)
+pub fn echo(line: &str) {
+    show(&format!("{}\n", line), false);
+    input(line);
+}
+
+/// Record a line typed by the player. (It's already on the screen.)
+pub fn input(line: &str) {
+    OUTPUT.with(|o| {
+        if let Some(t) = o.borrow_mut().transcript.as_mut() {
+            t.input(line);
+        }
+    });
+}
+
+/// A sink that keeps the output, to be sent on later.
+#[derive(Clone, Default)]
+pub struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+impl Buffer {
+    /// Take everything written so far.
+    pub fn take(&self) -> String {
+        let bytes = std::mem::take(&mut *self.0.borrow_mut());
+        String::from_utf8_lossy(&bytes).to_string()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Send all game output on this thread to the sink, instead of stdout.
+pub fn set_sink(sink: Sink) {
+    OUTPUT.with(|o| o.borrow_mut().sink = Some(sink));
+}
+
+/// Run `f`, and get back everything it said instead of showing it.
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let buffer = Buffer::default();
+    let sink = OUTPUT.with(|o| o.borrow_mut().sink.replace(Box::new(buffer.clone())));
+    let result = f();
+    OUTPUT.with(|o| o.borrow_mut().sink = sink);
+    (result, buffer.take())
+}
+
+/// Silence all game output, or let it be heard again.
+pub fn mute(muted: bool) {
+    OUTPUT.with(|o| o.borrow_mut().muted = muted);
+}
+
+fn is_muted() -> bool {
+    OUTPUT.with(|o| o.borrow().muted)
+}
+
+/// Is a transcript being recorded?
+pub fn is_recording() -> bool {
+    OUTPUT.with(|o| o.borrow().transcript.is_some())
+}
+
+/// Start copying everything into the transcript.
+pub fn start_transcript(transcript: Transcript) {
+    OUTPUT.with(|o| o.borrow_mut().transcript = Some(transcript));
+}
+
+/// Stop recording and hand back the transcript, if there was one.
+pub fn stop_transcript() -> Option<Transcript> {
+    OUTPUT.with(|o| o.borrow_mut().transcript.take())
+}
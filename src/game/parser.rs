@@ -1,13 +1,67 @@
-use super::{Action, Direction, GameContext, GameObject};
+use super::replay::Recorder;
+use super::{output, Action, Direction, GameContext, GameObject};
 use std::cell::Ref;
 use std::collections::VecDeque;
 use std::fmt::Debug;
-use std::io::{stdin, stdout, Write};
+use std::io::{self, stdin, BufRead};
 
-static SKIP_WORDS: [&str; 9] = ["a", "an", "at", "here", "of", "out", "the", "to", "with"];
+static SKIP_WORDS: [&str; 10] = [
+    "a", "an", "at", "here", "of", "on", "out", "the", "to", "with",
+];
+
+/// Every verb the parser understands, with its synonyms. The first word is the one shown to the player.
+/// ! Keep this in sync with the match arms below.
+pub static VERBS: [&[&str]; 39] = [
+    &["look", "desc", "describe"],
+    &["examine", "x", "explore", "inspect"],
+    &[
+        "go", "g", "ascend", "crawl", "descend", "run", "travel", "turn", "skip", "walk",
+    ],
+    &["climb"],
+    &["enter"],
+    &["exit", "leave"],
+    &["take", "get", "pick"],
+    &["drop", "d", "put", "place", "insert"],
+    &["open"],
+    &["close", "shut"],
+    &["lock"],
+    &["unlock"],
+    &["read", "r"],
+    &["use", "u"],
+    &["wear", "don"],
+    &["press", "push", "type", "dial"],
+    &[
+        "attack", "hit", "kick", "kill", "throw", "cut", "slice", "stab", "skewer", "slash",
+        "strike", "chop", "swing", "beat", "poke",
+    ],
+    &["light", "ignite", "burn", "switch"],
+    &["follow", "stalk"],
+    &["knock", "rap"],
+    &["listen", "play"],
+    &["wait"],
+    &["inventory", "i", "inv"],
+    &["map"],
+    &["verbose"],
+    &["brief"],
+    &["superbrief"],
+    &["script"],
+    &["unscript"],
+    &["diagnose", "health"],
+    &["score"],
+    &["guess", "hangman"],
+    &["version"],
+    &["save"],
+    &["restore"],
+    &["hint", "hints", "clue"],
+    &["help", "?"],
+    &["verbs"],
+    &["quit", "q"],
+];
 
-static _POSITION_WORDS: [&str; 10] = [
-    "above", "behind", "below", "beside", "beyond", "in", "inside", "near", "on", "under",
+/// Words for where something goes. After the object, they're skipped: "put ring in breadbox"
+static POSITION_WORDS: [&str; 12] = [
+    "above", "behind", "below", "beside", "beyond", "in", "inside", "into", "near", "on", "onto",
+    "under",
 ];
 
 // static ROOM_WORDS: [&str; 10] = [
@@ -100,17 +154,96 @@ impl Token {
     }
 }
 
+/// The parser reads lines from the player, or from a replay, and turns them into actions.
+/// Every line it consumes is kept, so the session can be recorded and replayed.
 #[derive(Default)]
-pub struct Parser;
+pub struct Parser {
+    restore: VecDeque<String>,  // lines from a saved game, played back silently
+    replay: VecDeque<String>,   // lines to play back before reading from stdin
+    replaying: bool,            // replay lines have been consumed
+    history: Vec<String>,       // every line consumed, in order
+    recorder: Option<Recorder>, // replay file being written
+    input: Option<Box<dyn BufRead>>, // where the player types, if not stdin
+}
 
 impl Parser {
-    fn read_line(&self) -> String {
-        print!("\n>> ");
-        stdout().flush().ok();
+    /// Feed these lines to the game before asking the player for more.
+    pub fn replay(&mut self, lines: Vec<String>) {
+        self.replay.extend(lines);
+    }
+
+    /// Read the player's lines from somewhere other than stdin.
+    pub fn set_input(&mut self, input: Box<dyn BufRead>) {
+        self.input = Some(input);
+    }
+
+    /// Write every line consumed, past and future, to a replay file.
+    pub fn record(&mut self, path: &str, seed: u64) -> io::Result<()> {
+        self.recorder = Some(Recorder::create(path, seed, &self.history)?);
+        Ok(())
+    }
+
+    /// Every line consumed so far.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Start over from a saved game: forget the history, and play these lines first.
+    pub fn restore(&mut self, lines: Vec<String>) {
+        self.history.clear();
+        self.restore = lines.into();
+    }
+
+    /// Are there lines from a saved game still to play?
+    pub fn is_restoring(&self) -> bool {
+        !self.restore.is_empty()
+    }
+
+    /// Read the next line from a saved game, the replay, or from stdin when the replay runs out.
+    /// Returns None at the end of input.
+    pub fn read_line(&mut self) -> Option<String> {
+        // Saved lines are already recorded, and the player doesn't need to see them.
+        if let Some(line) = self.restore.pop_front() {
+            self.history.push(line.clone());
+            return Some(line);
+        }
+
+        if self.replaying && self.replay.is_empty() {
+            self.replaying = false;
+            outln!("\n[End of replay. You have control.]");
+        }
+        output::prompt("\n>> ");
 
-        let mut input = String::new();
-        stdin().read_line(&mut input).unwrap();
-        input.trim().to_string()
+        let line = match self.replay.pop_front() {
+            Some(line) => {
+                output::echo(&line);
+                self.replaying = true;
+                line
+            }
+            None => {
+                let mut input = String::new();
+                let read = match self.input.as_mut() {
+                    Some(reader) => reader.read_line(&mut input),
+                    None => stdin().read_line(&mut input),
+                };
+                if read.unwrap_or(0) == 0 {
+                    outln!();
+                    return None;
+                }
+                output::input(input.trim());
+                input.trim().to_string()
+            }
+        };
+        Some(self.accept(line))
+    }
+
+    /// Take a line from the caller, instead of reading it. It's recorded like any other.
+    pub fn accept(&mut self, line: String) -> String {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record(&line);
+        }
+        self.history.push(line.clone());
+        line
     }
 
     /// Parser reads vector of tokens from stdin and returns Tuple(PRSA, PRSO, PRSI).
@@ -121,15 +254,18 @@ impl Parser {
 
         let lower = input.to_lowercase();
         for token in lower.split_whitespace() {
+            if tokens.len() > 1 && POSITION_WORDS.contains(&token) {
+                continue;
+            }
             if !SKIP_WORDS.contains(&token) {
                 tokens.push_back(token.to_string());
             }
         }
 
         if tokens.is_empty() {
-            return Token::from_action("help");
+            Token::from_action("help")
         } else if tokens.len() > 3 {
-            println!("'{:?}' is too many words.", tokens);
+            outln!("'{:?}' is too many words.", tokens);
             Token::from_action("help")
         } else {
             Token::from_vec(&mut tokens)
@@ -160,15 +296,26 @@ impl Parser {
                         Action::Attack(o, i)
                     }
                     "ignite" | "burn" | "light" | "switch" => Action::Light(o, i),
-                    "d" | "drop" => Action::Drop(o, i),
+                    "d" | "drop" | "put" | "place" | "insert" => Action::Drop(o, i),
                     "r" | "read" => Action::Read(o, i),
-                    "unlock" | "open" => Action::Open(o, i),
+                    "press" | "push" | "type" | "dial" => Action::Press(o, i),
+                    "open" => Action::Open(o, i),
+                    "close" | "shut" => Action::Close(o, i),
+                    "lock" => Action::Lock(o, i),
+                    "unlock" => Action::Unlock(o, i),
 
                     // The symantic meaning of "use" is "use indirect on object".
                     // Examples: "Use key" or "Use key on door" or "use key with door"
                     // PRSA: use, PRSO: door, PRSI: key
                     // TODO: "use key to unlock door" or "use key to open door"
-                    "u" | "use" => Action::Use(i.unwrap_or("".to_string()), Some(o)),
+                    // Using a weapon on something is an attack: "use knife on bread"
+                    "u" | "use" => {
+                        if context.find(&o).is_some_and(|obj| obj.weapon().is_some()) {
+                            Action::Attack(i.unwrap_or("".to_string()), Some(o))
+                        } else {
+                            Action::Use(i.unwrap_or("".to_string()), Some(o))
+                        }
+                    }
 
                     _ => Action::UnknownAction(token.prsa.to_string()),
                 };
@@ -185,8 +332,15 @@ impl Parser {
                     } else {
                         Action::MissingTarget(token.prsa)
                     }
-                } else if action.get_indirect_object().is_none() {
-                    let targets = self.get_targets(&action, context.inv());
+                } else if action.get_indirect_object().is_none()
+                    && !matches!(action, Action::Drop(..))
+                {
+                    // Buttons are pressed on things nearby. Everything else is done with things you carry.
+                    let nearby = match action {
+                        Action::Press(_, _) => context.locals(),
+                        _ => context.inv(),
+                    };
+                    let targets = self.get_targets(&action, nearby);
                     if targets.len() == 1 {
                         action.set_indirect_object(targets.first().unwrap().to_string())
                     } else {
@@ -203,15 +357,17 @@ impl Parser {
         // For brevity.
         let o = token.prso.clone();
 
-        return match token.prsa.as_str() {
+        match token.prsa.as_str() {
             "climb" => Action::Climb(o),
             "desc" | "describe" | "look" => Action::Describe(o),
             "follow" | "stalk" => Action::Follow(o),
+            "knock" | "rap" => Action::Knock(o),
             "listen" | "play" => Action::Listen(o),
             "take" | "get" | "pick" => Action::Take(o),
+            "wear" | "don" => Action::Wear(o),
             "x" | "examine" | "explore" | "inspect" => Action::Examine(o),
             &_ => self.to_indirect_action(token, context),
-        };
+        }
     }
 
     fn to_direction(&self, direction: String) -> Option<Direction> {
@@ -233,41 +389,77 @@ impl Parser {
         let prsa = token.prsa.as_str();
         match prsa {
             "i" | "inv" | "inventory" => Action::Inventory,
+            "diagnose" | "health" => Action::Diagnose,
             "q" | "quit" => Action::Quit,
-            "?" | "help" | "hint" => Action::Help,
-            "g" | "go" | "ascend" | "climb" | "crawl" | "descend" | "run" | "travel" | "turn"
-            | "skip" | "walk" => {
+            "?" | "help" => Action::Help,
+            "hint" | "hints" | "clue" => Action::Hint,
+            "map" => Action::Map,
+            "verbose" => Action::Verbose,
+            "brief" => Action::Brief,
+            "superbrief" => Action::Superbrief,
+            "script" => Action::Script(token.prso),
+            "unscript" => Action::Unscript,
+            "score" => Action::Score,
+            "guess" | "hangman" => Action::Guess,
+            "version" => Action::Version,
+            "save" => Action::Save(token.prso),
+            "restore" => Action::Restore(token.prso),
+            "verbs" => Action::Verbs,
+            "g" | "go" | "ascend" | "crawl" | "descend" | "run" | "travel" | "turn" | "skip"
+            | "walk" => {
                 match token.prso {
                     // Room chooses direction, usually the only visible entrance or exit.
                     None => Action::Go(Direction::Exit),
                     Some(dir) => {
+                        // Not a direction? Maybe it's a place: "go to kitchen"
                         if let Some(direction) = self.to_direction(dir.clone()) {
                             Action::Go(direction)
+                        } else if context.is_room(&dir) {
+                            Action::Travel(dir)
                         } else {
                             Action::UnknownDirection(dir)
                         }
                     }
                 }
             }
+            // Climbing a direction is going that way: "climb up". Anything else is climbed: "climb tree"
+            "climb" => match token.prso.clone().and_then(|dir| self.to_direction(dir)) {
+                Some(direction) => Action::Go(direction),
+                None => self.to_direct_action(token, context),
+            },
             "wait" => Action::Wait,
+            // Entering a number is pressing the keys: "enter 1234"
+            "enter"
+                if token
+                    .prso
+                    .as_ref()
+                    .is_some_and(|o| o.chars().all(|c| c.is_ascii_digit())) =>
+            {
+                self.to_indirect_action(
+                    Token {
+                        prsa: String::from("press"),
+                        ..token
+                    },
+                    context,
+                )
+            }
             "enter" => Action::Go(Direction::Enter),
             "leave" | "exit" => Action::Go(Direction::Exit),
             _ => self.to_direct_action(token, context),
         }
     }
 
-    // Parser parses the PRSA of the command and returns an Action enum.
-    pub fn input_action(&self, context: &GameContext) -> Action {
-        let token = self.parse_token(self.read_line());
-        self.to_action(token, context)
+    /// Parse a line of input into an action.
+    pub fn parse_line(&self, line: String, context: &GameContext) -> Action {
+        self.to_action(self.parse_token(line), context)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "world"))]
 mod tests {
     use super::*;
     use crate::game::atlas::GameAtlas;
-    use crate::game::objects::{forest, kitchen};
+    use crate::world::{forest, kitchen};
 
     fn setup_atlas() -> GameAtlas {
         let mut vec = Vec::new() as Vec<Box<dyn GameObject>>;
@@ -289,6 +481,29 @@ mod tests {
         assert_eq!(action, Action::Help);
     }
 
+    #[test]
+    fn test_parser_hint() {
+        let atlas = setup_atlas();
+        let token: Token = Token::from_action("hint");
+
+        let parser = Parser::default();
+        let action = parser.to_action(token, &atlas.get_context());
+        assert_eq!(action, Action::Hint);
+    }
+
+    #[test]
+    fn test_parser_knows_all_verbs() {
+        let atlas = setup_atlas();
+        let parser = Parser::default();
+        for synonyms in VERBS.iter() {
+            for word in synonyms.iter() {
+                let token = Token::from_object(word, "north");
+                let action = parser.to_action(token, &atlas.get_context());
+                assert_ne!(action, Action::UnknownAction(word.to_string()));
+            }
+        }
+    }
+
     #[test]
     fn test_parser_inventory() {
         let atlas = setup_atlas();
@@ -309,6 +524,18 @@ mod tests {
         assert_eq!(action, Action::Quit);
     }
 
+    #[test]
+    fn test_parser_save_restore() {
+        let atlas = setup_atlas();
+        let parser = Parser::default();
+
+        let action = parser.parse_line(String::from("save"), &atlas.get_context());
+        assert_eq!(action, Action::Save(None));
+
+        let action = parser.parse_line(String::from("restore mygame"), &atlas.get_context());
+        assert_eq!(action, Action::Restore(Some(String::from("mygame"))));
+    }
+
     #[test]
     fn test_parser_go_north() {
         let atlas = setup_atlas();
@@ -329,6 +556,18 @@ mod tests {
         assert_eq!(action, Action::Go(Direction::Exit));
     }
 
+    #[test]
+    fn test_parser_go_to_room() {
+        let atlas = setup_atlas();
+        let parser = Parser::default();
+        let token = parser.parse_token(String::from("walk to the kitchen"));
+        let action = parser.to_action(token, &atlas.get_context());
+        assert_eq!(action, Action::Travel(String::from("kitchen")));
+        let token = parser.parse_token(String::from("go nrth"));
+        let action = parser.to_action(token, &atlas.get_context());
+        assert_eq!(action, Action::UnknownDirection(String::from("nrth")));
+    }
+
     #[test]
     fn test_parser_look() {
         let atlas = setup_atlas();
@@ -345,6 +584,7 @@ mod tests {
         let mut atlas = setup_atlas();
         atlas.set_here(String::from("kitchen"));
         atlas.move_local(String::from("bread"));
+        atlas.move_inventory(String::from("knife"));
 
         let token = Token::from_object("use", "knife");
         let expected = Action::Attack(String::from("bread"), token.prso.clone());
@@ -363,4 +603,26 @@ mod tests {
         let token = parser.parse_token(input);
         assert_eq!(token, expected);
     }
+
+    #[test]
+    fn test_parser_put_in() {
+        let parser = Parser::default();
+        let token = parser.parse_token(String::from("put the ring into the breadbox"));
+        assert_eq!(token, Token::from_indirect("put", "ring", "breadbox"));
+        // Before the object, it's a direction.
+        let token = parser.parse_token(String::from("go in"));
+        assert_eq!(token, Token::from_object("go", "in"));
+    }
+
+    #[test]
+    fn test_parser_climb() {
+        let atlas = setup_atlas();
+        let parser = Parser::default();
+        let action = parser.parse_line(String::from("climb up"), &atlas.get_context());
+        assert_eq!(action, Action::Go(Direction::Up));
+        let action = parser.parse_line(String::from("climb knife"), &atlas.get_context());
+        assert_eq!(action, Action::Climb(Some(String::from("knife"))));
+        let action = parser.parse_line(String::from("climb"), &atlas.get_context());
+        assert_eq!(action, Action::Climb(None));
+    }
 }
@@ -0,0 +1,186 @@
+use super::GameAtlas;
+
+/// A test against the current state of the game, e.g. "is the key out of the leaves?"
+pub type Condition = fn(&GameAtlas) -> bool;
+
+/// Points deducted for each new hint.
+pub static HINT_PENALTY: i32 = 1;
+
+/// A hint for the puzzle the player is stuck on.
+#[derive(Debug, PartialEq)]
+pub struct Hint {
+    pub puzzle: String, // name of the puzzle
+    pub text: String,   // what to tell the player
+    pub cost: i32,      // points deducted
+}
+
+/// A puzzle is a goal the player can solve for points, with hints from vague to explicit.
+pub struct Puzzle {
+    name: String,
+    points: i32,
+    hints: Vec<String>,
+    ready: Condition,  // player can start working on this puzzle
+    solved: Condition, // player has solved this puzzle
+    shown: usize,      // number of hints given so far
+    done: bool,        // points have been awarded
+}
+
+impl Puzzle {
+    pub fn new(name: &str, points: i32, ready: Condition, solved: Condition) -> Self {
+        Self {
+            name: name.to_string(),
+            points,
+            hints: Vec::new(),
+            ready,
+            solved,
+            shown: 0,
+            done: false,
+        }
+    }
+
+    /// Add the next hint. Add them in order, from vague to explicit.
+    pub fn hint(mut self, text: &str) -> Self {
+        self.hints.push(text.to_string());
+        self
+    }
+
+    /// Is the player stuck on this puzzle right now?
+    fn is_stuck(&self, atlas: &GameAtlas) -> bool {
+        !self.done && (self.ready)(atlas) && !(self.solved)(atlas)
+    }
+}
+
+/// All puzzles in the game, in the order the player is expected to solve them.
+pub struct Puzzles {
+    list: Vec<Puzzle>,
+    penalty: i32, // points deducted for each new hint
+}
+
+impl Default for Puzzles {
+    fn default() -> Self {
+        Self::new(HINT_PENALTY)
+    }
+}
+
+impl Puzzles {
+    pub fn new(penalty: i32) -> Self {
+        Self {
+            list: Vec::new(),
+            penalty,
+        }
+    }
+
+    /// Add a list of puzzles to the game.
+    pub fn add_all(&mut self, puzzles: Vec<Puzzle>) {
+        self.list.extend(puzzles);
+    }
+
+    /// The most points the player can earn.
+    pub fn total(&self) -> i32 {
+        self.list.iter().map(|p| p.points).sum()
+    }
+
+    pub fn all_solved(&self) -> bool {
+        self.list.iter().all(|p| p.done)
+    }
+
+    /// Check for newly solved puzzles. Returns the points earned.
+    pub fn update(&mut self, atlas: &GameAtlas) -> i32 {
+        let mut points = 0;
+        for p in self.list.iter_mut().filter(|p| !p.done) {
+            if (p.solved)(atlas) {
+                p.done = true;
+                points += p.points;
+            }
+        }
+        points
+    }
+
+    /// Get the next hint for the earliest puzzle the player is stuck on.
+    /// Once all hints are given, the last one repeats for free.
+    pub fn next_hint(&mut self, atlas: &GameAtlas) -> Option<Hint> {
+        let puzzle = self.list.iter_mut().find(|p| p.is_stuck(atlas))?;
+        let last = puzzle.hints.len().checked_sub(1)?;
+        let hint = Hint {
+            puzzle: puzzle.name.clone(),
+            text: puzzle.hints[puzzle.shown.min(last)].clone(),
+            cost: if puzzle.shown <= last {
+                self.penalty
+            } else {
+                0
+            },
+        };
+        puzzle.shown += 1;
+        Some(hint)
+    }
+}
+
+#[cfg(all(test, feature = "world"))]
+mod tests {
+    use super::*;
+    use crate::game::GameObject;
+    use crate::world::{forest, kitchen};
+
+    fn setup() -> (GameAtlas, Puzzles) {
+        let mut vec = Vec::new() as Vec<Box<dyn GameObject>>;
+        forest::create(&mut vec);
+        kitchen::create(&mut vec);
+
+        let mut atlas = GameAtlas::new(vec[0].name());
+        atlas.add_all(vec);
+
+        let mut list = Vec::new() as Vec<Puzzle>;
+        forest::create_puzzles(&mut list);
+        kitchen::create_puzzles(&mut list);
+
+        let mut puzzles = Puzzles::new(1);
+        puzzles.add_all(list);
+        (atlas, puzzles)
+    }
+
+    #[test]
+    fn test_hints_progress() {
+        let (atlas, mut puzzles) = setup();
+        let first = puzzles.next_hint(&atlas).unwrap();
+        assert_eq!(first.puzzle, "find the key");
+        assert_eq!(first.cost, 1);
+
+        let second = puzzles.next_hint(&atlas).unwrap();
+        assert_ne!(first.text, second.text);
+
+        // The last hint repeats for free.
+        let last = puzzles.next_hint(&atlas).unwrap();
+        let again = puzzles.next_hint(&atlas).unwrap();
+        assert_eq!(again.text, last.text);
+        assert_eq!(again.cost, 0);
+    }
+
+    #[test]
+    fn test_hints_follow_solution() {
+        let (mut atlas, mut puzzles) = setup();
+        assert_eq!(puzzles.update(&atlas), 0);
+
+        // Finding the key moves on to the breadbox.
+        atlas.move_inventory(String::from(forest::KEY));
+        assert_eq!(puzzles.update(&atlas), 5);
+        let hint = puzzles.next_hint(&atlas).unwrap();
+        assert_eq!(hint.puzzle, "unlock the breadbox");
+        assert!(!puzzles.all_solved());
+    }
+
+    #[test]
+    fn test_missing_objects_solve_nothing() {
+        let (mut atlas, mut puzzles) = setup();
+        atlas.move_inventory(String::from(forest::KEY));
+        assert_eq!(puzzles.update(&atlas), 5);
+
+        // The bread is gone, but the breadbox is still locked and the ring still in the bread.
+        atlas.destroy_object(String::from(kitchen::BREAD));
+        assert_eq!(puzzles.update(&atlas), 0);
+
+        let mut lock = atlas.get_lockable(String::from(kitchen::BREADBOX)).unwrap();
+        lock.locked = false;
+        atlas.set_lockable(String::from(kitchen::BREADBOX), lock);
+        assert_eq!(puzzles.update(&atlas), 10);
+    }
+}
@@ -0,0 +1,97 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+
+/// First line of every replay file.
+static HEADER: &str = "zork-rust replay";
+
+/// A replay is the RNG seed and every line the player typed, in order.
+/// Feeding the same lines to a game with the same seed reproduces the session exactly.
+///
+/// The file format is plain text, so testers can edit it by hand:
+/// ```text
+/// zork-rust replay
+/// seed 1234
+/// kick leaves
+/// take key
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub lines: Vec<String>,
+}
+
+impl Replay {
+    /// Read a replay file.
+    pub fn load(path: &str) -> io::Result<Replay> {
+        let text = fs::read_to_string(path)?;
+        Replay::parse(&text).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "not a zork-rust replay file")
+        })
+    }
+
+    /// Parse the text of a replay file.
+    pub fn parse(text: &str) -> Option<Replay> {
+        let mut lines = text.lines();
+        if lines.next()?.trim() != HEADER {
+            return None;
+        }
+        let seed = lines.next()?.trim().strip_prefix("seed ")?.parse().ok()?;
+        Some(Replay {
+            seed,
+            lines: lines.map(|l| l.to_string()).collect(),
+        })
+    }
+
+    /// Keep only the first `turns` lines.
+    pub fn truncate(&mut self, turns: usize) {
+        self.lines.truncate(turns);
+    }
+}
+
+/// Writes a replay file as the game is played, one line at a time, so nothing is lost in a crash.
+pub struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    /// Start a new replay file with the seed and the lines typed so far.
+    pub fn create(path: &str, seed: u64, history: &[String]) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        writeln!(file, "{}\nseed {}", HEADER, seed)?;
+        for line in history {
+            writeln!(file, "{}", line)?;
+        }
+        Ok(Self { file })
+    }
+
+    /// Append a line typed by the player.
+    pub fn record(&mut self, line: &str) {
+        writeln!(self.file, "{}", line).ok();
+        self.file.flush().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let replay = Replay::parse("zork-rust replay\nseed 42\nkick leaves\n\ntake key\n").unwrap();
+        assert_eq!(replay.seed, 42);
+        assert_eq!(replay.lines, vec!["kick leaves", "", "take key"]);
+    }
+
+    #[test]
+    fn test_parse_bad_header() {
+        assert_eq!(Replay::parse("kick leaves\n"), None);
+        assert_eq!(Replay::parse("zork-rust replay\nseed lots\n"), None);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut replay = Replay::parse("zork-rust replay\nseed 7\nn\ns\ne\n").unwrap();
+        replay.truncate(2);
+        assert_eq!(replay.lines, vec!["n", "s"]);
+    }
+}
@@ -0,0 +1,204 @@
+use super::atlas::PLAYER;
+use super::output::{self, Buffer};
+use super::{Game, GameAtlas, Puzzles, World};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A world shared by several players, who take turns as their commands come in.
+/// Every player has a game of their own: health, score, map, and where they are and what they carry.
+/// The objects in the world belong to everyone. A puzzle solved by one is solved for all,
+/// and only the one who solved it gets the points.
+/// The world keeps its own time: it moves on one turn once every player could have had one.
+/// ! Captures all game output on this thread, to hand back to the player who caused it.
+pub struct SharedWorld {
+    world: World,                  // builds each player's game
+    atlas: GameAtlas,              // the one world everyone plays in
+    puzzles: Puzzles,              // the world's puzzles, solved once for everyone
+    start: String,                 // where new players appear
+    games: BTreeMap<String, Game>, // every player's game, by name
+    buffer: Buffer,                // output of the turn being played
+    clock: usize,                  // turns played since the world last moved on
+    news: Vec<(String, String)>,   // for other players: who to tell, what they saw happen
+}
+
+impl SharedWorld {
+    pub fn new(world: World) -> Self {
+        let (mut atlas, list) = world();
+        let start = atlas.here();
+        atlas.remove_player(PLAYER.to_string());
+        let mut puzzles = Puzzles::default();
+        puzzles.add_all(list);
+
+        let buffer = Buffer::default();
+        output::set_sink(Box::new(buffer.clone()));
+        Self {
+            world,
+            atlas,
+            puzzles,
+            start,
+            games: BTreeMap::new(),
+            buffer,
+            clock: 0,
+            news: Vec::new(),
+        }
+    }
+
+    /// Add a player. Returns their name, made unique, and what they see.
+    pub fn join(&mut self, name: &str) -> (String, String) {
+        let base = match name.trim() {
+            "" => "Adventurer",
+            name => name,
+        };
+        let mut name = base.to_string();
+        let mut n = 1;
+        while self.games.contains_key(&name) {
+            n += 1;
+            name = format!("{}{}", base, n);
+        }
+
+        outln!("Welcome, {}.", name);
+        let mut game = Game::new(self.world);
+        game.set_remote(true);
+        game.join(&mut self.atlas, name.clone(), self.start.clone());
+        output::prompt("\n>> ");
+        self.games.insert(name.clone(), game);
+        (name, self.buffer.take())
+    }
+
+    /// Play a line for the named player. Returns what they see, and whether they're still playing.
+    pub fn play(&mut self, name: &str, line: String) -> (String, bool) {
+        let (playing, turns) = match self.games.get_mut(name) {
+            Some(game) => {
+                let before = game.status().turns;
+                let playing = game.play_shared(&mut self.atlas, line);
+                let points = self.puzzles.update(&self.atlas);
+                game.award_shared(&mut self.atlas, points);
+                (playing, game.status().turns - before)
+            }
+            None => (false, 0),
+        };
+
+        // However many are playing, the world moves on once for every turn each of them has had.
+        self.clock += turns as usize;
+        while !self.games.is_empty() && self.clock >= self.games.len() {
+            self.clock -= self.games.len();
+            self.tick(name);
+        }
+        if playing {
+            output::prompt("\n>> ");
+        } else {
+            self.leave(name);
+        }
+        (self.buffer.take(), playing)
+    }
+
+    /// Remove a player from the world.
+    pub fn leave(&mut self, name: &str) {
+        if let Some(mut game) = self.games.remove(name) {
+            game.leave(&mut self.atlas);
+        }
+    }
+
+    /// Move the world on one turn. Scheduled events fire, wounds heal, and things near each player act,
+    /// once each. Everyone sees what happens near them. The player who moved sees the rest.
+    fn tick(&mut self, mover: &str) {
+        let turn = self.buffer.take();
+        self.atlas.set_player(mover.to_string());
+        self.atlas.tick_events();
+        let woke = self.atlas.heal_all();
+        let mut seen = self.buffer.take();
+
+        let mut ticked = BTreeSet::new();
+        for (name, game) in self.games.iter_mut() {
+            game.tick_shared(&mut self.atlas, &mut ticked, &woke);
+            let text = self.buffer.take();
+            if name == mover {
+                seen.push_str(&text);
+            } else if !text.trim().is_empty() {
+                self.news.push((name.clone(), text.trim().to_string()));
+            }
+        }
+        out!("{}{}", turn, seen);
+    }
+
+    /// Take the messages for other players: who to tell, and what to say.
+    pub fn take_news(&mut self) -> Vec<(String, String)> {
+        let mut news = self.atlas.take_news();
+        news.append(&mut self.news);
+        news
+    }
+}
+
+#[cfg(all(test, feature = "world"))]
+mod tests {
+    use super::*;
+    use crate::world;
+
+    #[test]
+    fn test_shared_world() {
+        let mut world = SharedWorld::new(world::create_world);
+        let (alice, _) = world.join("Alice");
+        let (bob, text) = world.join("Bob");
+        assert!(text.contains("Alice is here."));
+        assert_eq!(world.join("Alice").0, "Alice2");
+        world.leave("Alice2");
+        world.take_news();
+
+        world.play(&alice, String::from("kick leaves"));
+        world.play(&alice, String::from("take key"));
+        assert_eq!(
+            world.take_news(),
+            vec![(bob.clone(), String::from("Alice takes the key."))]
+        );
+
+        // The key is gone for everyone, and only Alice carries it.
+        let (text, _) = world.play(&bob, String::from("inventory"));
+        assert!(text.contains("You are not carrying anything."));
+        let (text, _) = world.play(&alice, String::from("inventory"));
+        assert!(text.contains("You are carrying:"));
+
+        let (_, playing) = world.play(&alice, String::from("quit"));
+        assert!(!playing);
+        assert_eq!(
+            world.take_news(),
+            vec![(bob.clone(), String::from("Alice vanishes."))]
+        );
+        let (text, _) = world.play(&bob, String::from("look"));
+        assert!(text.contains("A shiny key"));
+    }
+
+    #[test]
+    fn test_shared_clock() {
+        let mut world = SharedWorld::new(world::create_world);
+        let (alice, _) = world.join("Alice");
+        let (bob, _) = world.join("Bob");
+        let raccoon = String::from(world::forest::RACCOON);
+        let mut health = world.atlas.get_health(raccoon.clone()).unwrap();
+        health.wound(5);
+        world.atlas.set_health(raccoon.clone(), health);
+
+        // Two players, two turns each: the world has moved on two turns, not four.
+        for _ in 0..2 {
+            world.play(&alice, String::from("wait"));
+            world.play(&bob, String::from("wait"));
+        }
+        assert!(!world
+            .atlas
+            .get_health(raccoon.clone())
+            .unwrap()
+            .is_conscious());
+        world.take_news();
+        world.play(&alice, String::from("wait"));
+        let (text, _) = world.play(&bob, String::from("wait"));
+        assert!(text.contains("The raccoon stirs"));
+        assert!(world
+            .take_news()
+            .iter()
+            .any(|(to, text)| *to == alice && text.contains("The raccoon stirs")));
+
+        // Alice finds the key. Bob doesn't get the points for it.
+        world.play(&alice, String::from("kick leaves"));
+        world.play(&bob, String::from("wait"));
+        assert_eq!(world.games[&alice].status().score, 5);
+        assert_eq!(world.games[&bob].status().score, 0);
+    }
+}
@@ -1,5 +1,5 @@
-static TITLE: &'static str = include_str!("title.txt");
+pub static TITLE: &str = include_str!("title.txt");
 
 pub fn print() {
-    println!("{}\n\n", &TITLE)
+    outln!("{}\n\n", &TITLE)
 }
@@ -0,0 +1,265 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Transcript file formats. The plain text file is always written as you play.
+/// Markdown and HTML are exported alongside it when the transcript is finished.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Markdown,
+    Html,
+}
+
+impl Format {
+    /// Parse a format name, as typed by the player: "SCRIPT HTML"
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name.to_lowercase().as_str() {
+            "text" | "txt" => Some(Format::Text),
+            "markdown" | "md" => Some(Format::Markdown),
+            "html" | "htm" => Some(Format::Html),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Text => "txt",
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Entry {
+    Prompt(String),
+    Input(String),
+    Output(String),
+}
+
+/// A record of everything said and typed during a session.
+pub struct Transcript {
+    path: PathBuf,       // the plain text file
+    format: Format,      // export format, when finished
+    file: Option<File>,  // None when recording in memory only (for testing)
+    started: String,     // when recording began
+    entries: Vec<Entry>, // everything recorded so far
+}
+
+/// Current time in UTC, as "YYYY-MM-DD HH:MM:SS".
+pub fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (days, rem) = (secs / 86400, secs % 86400);
+
+    // Convert days since 1970-01-01 into a civil date. (Howard Hinnant's algorithm.)
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+impl Transcript {
+    /// Start a new transcript file in the current directory, named for the time it started.
+    pub fn create(format: Format) -> io::Result<Self> {
+        let started = timestamp();
+        let stamp: String = started.chars().filter(|c| c.is_ascii_digit()).collect();
+        let name = format!("transcript-{}-{}", &stamp[..8], &stamp[8..]);
+
+        // Don't overwrite a transcript started in the same second.
+        let mut path = PathBuf::from(format!("{}.txt", name));
+        let mut n = 1;
+        while path.exists() {
+            n += 1;
+            path = PathBuf::from(format!("{}-{}.txt", name, n));
+        }
+
+        let mut file = File::create(&path)?;
+        writeln!(file, "Transcript started {} UTC\n", started)?;
+
+        Ok(Self {
+            path,
+            format,
+            file: Some(file),
+            started,
+            entries: Vec::new(),
+        })
+    }
+
+    /// Record in memory only. Nothing is written to disk.
+    #[allow(dead_code)]
+    pub fn in_memory(format: Format) -> Self {
+        Self {
+            path: PathBuf::new(),
+            format,
+            file: None,
+            started: timestamp(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn record(&mut self, entry: Entry) {
+        if let Some(file) = self.file.as_mut() {
+            let text = match &entry {
+                Entry::Prompt(t) | Entry::Output(t) => t.clone(),
+                Entry::Input(t) => format!("{}\n", t),
+            };
+            file.write_all(text.as_bytes()).ok();
+        }
+        // Merge output, so each turn is one block.
+        match (self.entries.last_mut(), entry) {
+            (Some(Entry::Output(last)), Entry::Output(text)) => last.push_str(&text),
+            (_, entry) => self.entries.push(entry),
+        }
+    }
+
+    pub fn prompt(&mut self, text: &str) {
+        self.record(Entry::Prompt(text.to_string()));
+    }
+
+    pub fn input(&mut self, line: &str) {
+        self.record(Entry::Input(line.to_string()));
+    }
+
+    pub fn output(&mut self, text: &str) {
+        self.record(Entry::Output(text.to_string()));
+    }
+
+    /// Render the transcript as Markdown. Commands are bold, game output is preformatted.
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Transcript\n\n*Recorded {} UTC*\n\n", self.started);
+        for entry in self.entries.iter() {
+            match entry {
+                Entry::Prompt(_) => {}
+                Entry::Input(line) => md.push_str(&format!("**> {}**\n\n", line)),
+                Entry::Output(text) if text.trim().is_empty() => {}
+                Entry::Output(text) => {
+                    md.push_str(&format!("```text\n{}\n```\n\n", text.trim_matches('\n')))
+                }
+            }
+        }
+        md
+    }
+
+    /// Render the transcript as a standalone HTML page.
+    pub fn to_html(&self) -> String {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        };
+        let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Transcript</title>\n");
+        html.push_str(
+            "<style>body { font-family: monospace; } .input { font-weight: bold; }</style>\n",
+        );
+        html.push_str("</head>\n<body>\n");
+        html.push_str(&format!(
+            "<h1>Transcript</h1>\n<p><em>Recorded {} UTC</em></p>\n",
+            self.started
+        ));
+        for entry in self.entries.iter() {
+            match entry {
+                Entry::Prompt(_) => {}
+                Entry::Input(line) => {
+                    html.push_str(&format!("<p class=\"input\">&gt; {}</p>\n", escape(line)))
+                }
+                Entry::Output(text) if text.trim().is_empty() => {}
+                Entry::Output(text) => html.push_str(&format!(
+                    "<pre class=\"output\">{}</pre>\n",
+                    escape(text.trim_matches('\n'))
+                )),
+            }
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Close the transcript, and export it in the chosen format.
+    /// Returns the files written.
+    pub fn finish(self) -> io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        if self.file.is_none() {
+            return Ok(paths);
+        }
+        paths.push(self.path.clone());
+
+        let export = match self.format {
+            Format::Text => return Ok(paths),
+            Format::Markdown => self.to_markdown(),
+            Format::Html => self.to_html(),
+        };
+        let path = self.path.with_extension(self.format.extension());
+        std::fs::write(&path, export)?;
+        paths.push(path);
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Transcript {
+        let mut t = Transcript::in_memory(Format::Markdown);
+        t.output("FOREST\nYou are in a forest.\n");
+        t.prompt("\n>> ");
+        t.input("kick leaves");
+        t.output("The leaves flutter ");
+        t.output("and fly.\n");
+        t
+    }
+
+    #[test]
+    fn test_markdown() {
+        let md = setup().to_markdown();
+        assert!(md.contains("```text\nFOREST\nYou are in a forest.\n```"));
+        assert!(md.contains("**> kick leaves**"));
+        assert!(md.contains("```text\nThe leaves flutter and fly.\n```"));
+        assert!(!md.contains(">>"));
+    }
+
+    #[test]
+    fn test_html_escapes() {
+        let mut t = setup();
+        t.input("say <hello> & goodbye");
+        let html = t.to_html();
+        assert!(html.contains("<p class=\"input\">&gt; say &lt;hello&gt; &amp; goodbye</p>"));
+        assert!(html.contains("<pre class=\"output\">The leaves flutter and fly.</pre>"));
+    }
+
+    #[test]
+    fn test_format_names() {
+        assert_eq!(Format::from_name("MD"), Some(Format::Markdown));
+        assert_eq!(Format::from_name("html"), Some(Format::Html));
+        assert_eq!(Format::from_name("pdf"), None);
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let stamp = timestamp();
+        assert_eq!(stamp.len(), 19);
+        assert!(stamp.starts_with("20"));
+    }
+}
@@ -0,0 +1,25 @@
+//! A Zork-like text adventure engine.
+//!
+//! A world is a set of objects that respond to actions: rooms, things to take, creatures to fight.
+//! Build a `GameAtlas`, add your objects to it, and hand it to a `Game` with the puzzles to solve.
+//! Then play it a turn at a time, and get back what the player would see and what changed.
+//!
+//! The forest, garden and kitchen in `world` are the example world played by the binary.
+//! It's built with the `world` feature, which is on by default.
+//! ! Game output goes through the `out!` and `outln!` macros. Use them in your objects too.
+
+#[macro_use]
+mod game;
+pub mod cli;
+mod mini;
+mod protocol;
+mod server;
+#[cfg(feature = "world")]
+pub mod world;
+
+pub use game::{
+    output, Action, Change, Container, Direction, Effect, Event, Factory, Game, GameAtlas,
+    GameObject, GameRng, Handled, Health, Location, Lockable, Notify, Puzzle, Status, Token,
+    TurnResult, Weapon, World, INVENTORY, NOWHERE,
+};
+pub use mini::{MiniGame, Outcome};
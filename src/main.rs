@@ -1,23 +1,7 @@
-use game::{
-    objects::{
-        forest::{self, FOREST},
-        kitchen,
-    },
-    Game, GameAtlas, GameObject,
-};
+use std::env;
+use zork_rust::{cli, world};
 
-mod game;
-
-fn main() -> () {
-    game::title::print();
-
-    let mut vec = Vec::new() as Vec<Box<dyn GameObject>>;
-    forest::create(&mut vec);
-    kitchen::create(&mut vec);
-
-    let mut atlas = GameAtlas::new(String::from(FOREST));
-    atlas.add_all(vec);
-
-    let mut game = Game::new(atlas);
-    game.run();
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    cli::run(&args, world::create_world);
 }
@@ -1,22 +1,255 @@
-static WORD: &'static str = include_str!("guess.txt");
+use super::{MiniGame, Outcome};
+use crate::game::GameRng;
+use rand::seq::SliceRandom;
 
+/// The bundled word list, one word per line.
+static WORDS: &str = include_str!("guess.txt");
 
-pub fn run() {
-    let letters = WORD.chars().collect::<Vec<char>>();
+/// Wrong guesses allowed before the game is lost.
+pub static MAX_MISSES: usize = 6;
 
-    loop {
+/// What became of a single guess.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Guessed {
+    Hit,     // the letter is in the word
+    Miss,    // the letter isn't in the word
+    Again,   // the letter was guessed before
+    Invalid, // not a letter
+}
+
+/// A game of hangman: guess the word one letter at a time, before you run out of misses.
+pub struct Guess {
+    word: String,          // the word to guess
+    guesses: Vec<char>,    // every letter guessed, in order
+    max_misses: usize,     // wrong guesses allowed
+    over: Option<Outcome>, // how the last word ended, while asking to play again
+}
+
+impl Guess {
+    /// Start a game. The word is picked at random from the word list when play starts.
+    pub fn new() -> Self {
+        Self::with_word("")
+    }
+
+    /// Pick a word at random from the word list.
+    fn pick(rng: &mut GameRng) -> &'static str {
+        let words: Vec<&str> = WORDS.lines().filter(|w| !w.is_empty()).collect();
+        words.choose(rng).unwrap_or(&"raccoon")
+    }
+
+    /// Start a game with a chosen word.
+    pub fn with_word(word: &str) -> Self {
+        Self {
+            word: word.trim().to_lowercase(),
+            guesses: Vec::new(),
+            max_misses: MAX_MISSES,
+            over: None,
+        }
+    }
+
+    /// Guess a letter.
+    pub fn guess(&mut self, letter: char) -> Guessed {
+        let letter = letter.to_ascii_lowercase();
+        if !letter.is_ascii_lowercase() {
+            Guessed::Invalid
+        } else if self.guesses.contains(&letter) {
+            Guessed::Again
+        } else {
+            self.guesses.push(letter);
+            if self.word.contains(letter) {
+                Guessed::Hit
+            } else {
+                Guessed::Miss
+            }
+        }
+    }
+
+    /// Guess the whole word. A wrong word counts as a miss.
+    pub fn guess_word(&mut self, word: &str) -> Guessed {
+        if word.to_lowercase() == self.word {
+            for letter in self.word.clone().chars() {
+                if !self.guesses.contains(&letter) {
+                    self.guesses.push(letter);
+                }
+            }
+            Guessed::Hit
+        } else {
+            // A wrong word is kept as '*', which is never in the word, so it counts as a miss.
+            self.guesses.push('*');
+            Guessed::Miss
+        }
+    }
+
+    /// Letters guessed that aren't in the word.
+    pub fn misses(&self) -> Vec<char> {
+        self.guesses
+            .iter()
+            .filter(|c| !self.word.contains(**c))
+            .cloned()
+            .collect()
+    }
+
+    /// The word so far, with blanks for letters not yet guessed: "r a _ _ o o _"
+    pub fn masked(&self) -> String {
+        self.word
+            .chars()
+            .map(|c| if self.guesses.contains(&c) { c } else { '_' })
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.word.chars().all(|c| self.guesses.contains(&c))
+    }
+
+    pub fn is_lost(&self) -> bool {
+        self.misses().len() >= self.max_misses
+    }
+
+    /// Print the word so far, and the misses.
+    fn print(&self) {
+        let misses: String = self.misses().iter().filter(|c| **c != '*').collect();
+        outln!(
+            "\n  {}    (misses: {} of {}) {}",
+            self.masked(),
+            self.misses().len(),
+            self.max_misses,
+            misses
+        );
+    }
+}
+
+impl Default for Guess {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
+impl MiniGame for Guess {
+    fn name(&self) -> String {
+        String::from("guess")
     }
-    let mut guessed = vec![' '; letters.len()];
-    let mut wrong = 0;
-    let mut correct = 0;
-    let mut guesses = Vec::new();
-    let mut input = String::new();
-    let mut rng = rand::thread_rng();
-    let mut word_list = Vec::new();
-    let mut last_word = Vec::new();
-    let file = std::fs::read("corncob_lowercase.txt").unwrap();
 
+    fn start(&mut self, rng: &mut GameRng) {
+        if self.word.is_empty() {
+            self.word = Guess::pick(rng).to_string();
+        }
+        outln!("Let's play GUESS. Guess the word one letter at a time.");
+        outln!(
+            "{} wrong guesses and you lose. Type QUIT to give up.",
+            self.max_misses
+        );
+        self.print();
+    }
+
+    fn input(&mut self, line: &str, rng: &mut GameRng) -> Option<Outcome> {
+        let line = line.trim().to_lowercase();
+
+        // Between words: play again, or stop with the last outcome.
+        if let Some(outcome) = self.over {
+            if !line.starts_with('y') {
+                return Some(outcome);
+            }
+            *self = Guess::with_word(Guess::pick(rng));
+            self.print();
+            return None;
+        }
+
+        let guessed = match line.as_str() {
+            "" => Guessed::Invalid,
+            "q" | "quit" => {
+                outln!("You give up. The word was \"{}\".", self.word);
+                return Some(Outcome::Aborted);
+            }
+            _ if line.chars().count() == 1 => self.guess(line.chars().next().unwrap()),
+            _ => self.guess_word(&line),
+        };
+        match guessed {
+            Guessed::Hit => outln!("Yes!"),
+            Guessed::Miss => outln!("No."),
+            Guessed::Again => outln!("You already guessed that."),
+            Guessed::Invalid => outln!("Guess a letter, or the whole word. QUIT to give up."),
+        }
+
+        if self.is_won() {
+            outln!("\n  {}\n\nYou guessed it!", self.masked());
+            self.over = Some(Outcome::Won);
+        } else if self.is_lost() {
+            outln!("Out of guesses. The word was \"{}\".", self.word);
+            self.over = Some(Outcome::Lost);
+        } else {
+            self.print();
+        }
+        if self.over.is_some() {
+            outln!("Play again? (y/n)");
+        }
+        None
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
 
+    #[test]
+    fn test_guess_win() {
+        let mut game = Guess::with_word("oak");
+        assert_eq!(game.guess('o'), Guessed::Hit);
+        assert_eq!(game.guess('O'), Guessed::Again);
+        assert_eq!(game.guess('z'), Guessed::Miss);
+        assert_eq!(game.guess('1'), Guessed::Invalid);
+        assert_eq!(game.masked(), "o _ _");
+        assert_eq!(game.misses(), vec!['z']);
+        game.guess('a');
+        game.guess('k');
+        assert!(game.is_won());
+        assert!(!game.is_lost());
+    }
+
+    #[test]
+    fn test_guess_lose() {
+        let mut game = Guess::with_word("oak");
+        for letter in "bcdefg".chars() {
+            assert!(!game.is_lost());
+            game.guess(letter);
+        }
+        assert!(game.is_lost());
+        assert!(!game.is_won());
+    }
+
+    #[test]
+    fn test_guess_word() {
+        let mut game = Guess::with_word("oak");
+        assert_eq!(game.guess_word("elm"), Guessed::Miss);
+        assert_eq!(game.misses().len(), 1);
+        assert_eq!(game.guess_word("OAK"), Guessed::Hit);
+        assert!(game.is_won());
+    }
+
+    #[test]
+    fn test_guess_play_again() {
+        let mut rng = GameRng::seed_from_u64(7);
+        let mut game = Guess::with_word("oak");
+        assert_eq!(game.input("oak", &mut rng), None);
+        assert_eq!(game.input("y", &mut rng), None);
+        assert!(!game.is_won());
+        assert_eq!(game.input("quit", &mut rng), Some(Outcome::Aborted));
+
+        let mut game = Guess::with_word("oak");
+        game.input("oak", &mut rng);
+        assert_eq!(game.input("n", &mut rng), Some(Outcome::Won));
+    }
+
+    #[test]
+    fn test_seeded_word() {
+        let mut first = Guess::new();
+        first.start(&mut GameRng::seed_from_u64(7));
+        let mut again = Guess::new();
+        again.start(&mut GameRng::seed_from_u64(7));
+        let (first, again) = (first.word, again.word);
+        assert_eq!(first, again);
+        assert!(WORDS.lines().any(|w| w == first));
+    }
+}
@@ -1 +1,38 @@
+use crate::game::{parser::Parser, GameRng};
 
+pub mod guess;
+
+/// How a mini game ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Won,
+    Lost,
+    Aborted, // the player gave up, or ran out of input
+}
+
+/// A game within the game, with its own unique interaction.
+/// While a mini game is running, every line the player types goes to it instead of the parser.
+/// ! Lines still come through the parser, so mini games are recorded, replayed and restored like everything else.
+pub trait MiniGame {
+    fn name(&self) -> String;
+
+    /// Take over. Explain the rules and show the starting position.
+    fn start(&mut self, rng: &mut GameRng);
+
+    /// Handle a line typed by the player. Returns the outcome when the game is over.
+    fn input(&mut self, line: &str, rng: &mut GameRng) -> Option<Outcome>;
+}
+
+/// Play a mini game on its own, outside the adventure.
+pub fn run(game: &mut dyn MiniGame, rng: &mut GameRng, parser: &mut Parser) -> Outcome {
+    game.start(rng);
+    loop {
+        let line = match parser.read_line() {
+            Some(line) => line,
+            None => return Outcome::Aborted,
+        };
+        if let Some(outcome) = game.input(&line, rng) {
+            return outcome;
+        }
+    }
+}
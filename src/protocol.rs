@@ -0,0 +1,157 @@
+use crate::game::{Game, TurnResult};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+/// What a program can ask of the game, one JSON request per line:
+/// ```text
+/// {"command": "take key"}
+/// {"save": "mygame"}
+/// {"restore": null}
+/// "undo"
+/// ```
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Request {
+    Command(String),         // anything the player could type
+    Save(Option<String>),    // the name of the saved game, or null for the default
+    Restore(Option<String>), // ditto
+    Undo,                    // take back the last command
+}
+
+/// What the game says back, once per turn, as one line of JSON.
+/// Once the game has ended, it takes no more requests.
+#[derive(Debug, Serialize)]
+pub struct Response {
+    #[serde(flatten)]
+    pub result: TurnResult,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>, // the request made no sense
+}
+
+/// Play a request.
+fn play(game: &mut Game, request: Request) -> TurnResult {
+    match request {
+        Request::Command(line) => game.step(&line),
+        Request::Save(name) => game.capture(|game| game.save(name)),
+        Request::Restore(name) => game.capture(|game| game.restore(name)),
+        Request::Undo => game.step_back(),
+    }
+}
+
+/// Play the game for a program: read JSON requests, and write a JSON response to each.
+/// The first response, before any request, describes where the game begins.
+pub fn run(game: &mut Game, input: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+    let mut respond = |result: TurnResult, error: Option<String>| -> io::Result<()> {
+        let response = Response { result, error };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        writer.flush()
+    };
+
+    respond(game.start(), None)?;
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                let result = play(game, request);
+                let ended = result.ended;
+                respond(result, None)?;
+                if ended {
+                    break;
+                }
+            }
+            Err(e) => {
+                let result = TurnResult {
+                    status: game.status(),
+                    ..TurnResult::default()
+                };
+                respond(result, Some(e.to_string()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "world"))]
+mod tests {
+    use super::*;
+    use crate::world;
+    use serde_json::Value;
+
+    #[test]
+    fn test_requests() {
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"command": "take key"}"#).unwrap(),
+            Request::Command(String::from("take key"))
+        );
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"save": null}"#).unwrap(),
+            Request::Save(None)
+        );
+        assert_eq!(
+            serde_json::from_str::<Request>(r#""undo""#).unwrap(),
+            Request::Undo
+        );
+        assert!(serde_json::from_str::<Request>(r#"{"dance": true}"#).is_err());
+    }
+
+    #[test]
+    fn test_json_session() {
+        let input = [
+            r#"{"command": "kick leaves"}"#,
+            r#"{"command": "take key"}"#,
+            r#""undo""#,
+            r#"not json"#,
+            r#"{"command": "quit"}"#,
+            r#"{"command": "look"}"#,
+        ]
+        .join("\n");
+        let mut written = Vec::new();
+        let mut game = Game::new(world::create_world);
+        run(&mut game, input.as_bytes(), &mut written).unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(written)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 6, "nothing is played after the game ends");
+
+        assert_eq!(responses[0]["room"], "forest");
+        assert!(responses[0]["output"].as_str().unwrap().contains("FOREST"));
+
+        assert!(responses[1]["objects"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from("key")));
+        assert_eq!(responses[1]["turns"], 1);
+
+        assert_eq!(responses[2]["inventory"], Value::from(vec!["key"]));
+        assert_eq!(responses[2]["score"], 5);
+
+        assert_eq!(responses[3]["inventory"], Value::from(Vec::<String>::new()));
+        assert_eq!(responses[3]["turns"], 1);
+
+        assert!(responses[4]["error"].is_string());
+        assert_eq!(responses[5]["ended"], true);
+    }
+
+    #[test]
+    fn test_save_and_restore_by_name() {
+        let name = "Zork Protocol Test";
+        let mut game = Game::new(world::create_world);
+        game.start();
+        game.step("kick leaves");
+        let saved = play(&mut game, Request::Save(Some(name.to_string())));
+        assert!(saved.output.contains(name), "{}", saved.output);
+
+        game.step("take key");
+        let restored = play(&mut game, Request::Restore(Some(name.to_string())));
+        assert!(restored.output.contains("Restored"), "{}", restored.output);
+        assert!(restored.status.inventory.is_empty());
+        assert!(restored.status.objects.contains(&String::from("key")));
+        let _ = std::fs::remove_file(format!("saves/{}.sav", name));
+    }
+}
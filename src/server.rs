@@ -0,0 +1,404 @@
+use crate::game::{self, output, shared::SharedWorld, Game, World};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Where the server listens, unless told otherwise. Local connections only.
+pub static DEFAULT_ADDRESS: &str = "127.0.0.1:4000";
+
+/// Telnet commands. (See RFC 854.)
+const IAC: u8 = 255; // interpret as command
+const SB: u8 = 250; // start of subnegotiation
+const SE: u8 = 240; // end of subnegotiation
+const WILL: u8 = 251;
+const DONT: u8 = 254;
+
+/// Server settings.
+pub struct Config {
+    pub max_players: usize, // connections allowed at once
+    pub idle: Duration,     // disconnect players who type nothing for this long
+    pub shared: bool,       // everyone plays in one world, instead of a world each
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_players: 8,
+            idle: Duration::from_secs(300),
+            shared: false,
+        }
+    }
+}
+
+/// Where we are in the telnet byte stream.
+#[derive(Clone, Copy)]
+enum Telnet {
+    Data,       // text typed by the player
+    Command,    // after IAC
+    Option,     // after IAC WILL/WONT/DO/DONT
+    Sub,        // inside a subnegotiation
+    SubCommand, // IAC inside a subnegotiation
+}
+
+/// Reads what the player types, without telnet commands or carriage returns.
+/// Hangs up politely when the player has been idle too long.
+struct TelnetReader {
+    stream: TcpStream,
+    state: Telnet,
+}
+
+impl TelnetReader {
+    fn new(stream: &TcpStream) -> io::Result<BufReader<Self>> {
+        Ok(BufReader::new(Self {
+            stream: stream.try_clone()?,
+            state: Telnet::Data,
+        }))
+    }
+}
+
+impl Read for TelnetReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut raw = [0u8; 512];
+        loop {
+            let size = raw.len().min(buf.len());
+            let n = match self.stream.read(&mut raw[..size]) {
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
+                    self.stream
+                        .write_all(b"\r\n\r\nYou've been idle too long. Goodbye!\r\n")
+                        .ok();
+                    return Ok(0);
+                }
+                Err(e) => return Err(e),
+            };
+            if n == 0 {
+                return Ok(0);
+            }
+
+            let mut len = 0;
+            for &b in raw[..n].iter() {
+                self.state = match (self.state, b) {
+                    (Telnet::Data, IAC) => Telnet::Command,
+                    (Telnet::Data, b'\r') | (Telnet::Data, 0) => Telnet::Data,
+                    (Telnet::Data, b) => {
+                        buf[len] = b;
+                        len += 1;
+                        Telnet::Data
+                    }
+                    (Telnet::Command, SB) => Telnet::Sub,
+                    (Telnet::Command, WILL..=DONT) => Telnet::Option,
+                    (Telnet::Command, _) | (Telnet::Option, _) => Telnet::Data,
+                    (Telnet::Sub, IAC) => Telnet::SubCommand,
+                    (Telnet::Sub, _) => Telnet::Sub,
+                    (Telnet::SubCommand, SE) => Telnet::Data,
+                    (Telnet::SubCommand, _) => Telnet::Sub,
+                };
+            }
+            // Only telnet commands? Keep reading.
+            if len > 0 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+/// Writes game output with telnet line endings.
+struct TelnetWriter {
+    stream: TcpStream,
+}
+
+impl Write for TelnetWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf).replace('\n', "\r\n");
+        self.stream.write_all(text.as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+/// Play a whole game over one connection. Everything the game says goes back down the wire.
+fn play(stream: TcpStream, world: World, config: &Config) -> io::Result<()> {
+    stream.set_read_timeout(Some(config.idle))?;
+    output::set_sink(Box::new(TelnetWriter {
+        stream: stream.try_clone()?,
+    }));
+
+    game::title::print();
+    let mut game = Game::new(world);
+    game.set_remote(true);
+    game.set_input(Box::new(TelnetReader::new(&stream)?));
+    game.run();
+    outln!("Thanks for playing. Goodbye!");
+    Ok(())
+}
+
+/// What happens to players in a shared world. Sent from their connections to the world.
+enum Event {
+    Join(usize, String, Sender<Option<String>>), // id, name, where to send text (None to hang up)
+    Line(usize, String),                         // id, what they typed
+    Leave(usize),                                // id
+}
+
+/// Connect a player to the shared world: send what they type, and show them what comes back.
+fn connect(stream: TcpStream, id: usize, events: Sender<Event>, config: &Config) -> io::Result<()> {
+    stream.set_read_timeout(Some(config.idle))?;
+    let mut reader = TelnetReader::new(&stream)?;
+    let mut writer = TelnetWriter {
+        stream: stream.try_clone()?,
+    };
+
+    write!(writer, "{}\n\n", game::title::TITLE)?;
+    writer.write_all(b"What's your name? ")?;
+    let mut name = String::new();
+    if reader.read_line(&mut name)? == 0 {
+        return Ok(());
+    }
+
+    let (replies, inbox) = channel::<Option<String>>();
+    events
+        .send(Event::Join(id, name.trim().to_string(), replies))
+        .ok();
+    let printer = thread::spawn(move || {
+        while let Ok(Some(text)) = inbox.recv() {
+            if writer.write_all(text.as_bytes()).is_err() {
+                break;
+            }
+        }
+        writer.stream.shutdown(Shutdown::Both).ok();
+    });
+
+    for line in reader.lines() {
+        match line {
+            Ok(line) => events.send(Event::Line(id, line)).ok(),
+            Err(_) => break,
+        };
+    }
+    events.send(Event::Leave(id)).ok();
+    printer.join().ok();
+    Ok(())
+}
+
+/// Run the shared world: one turn at a time, in the order commands arrive.
+fn run_world(world: World, events: Receiver<Event>) {
+    let mut world = SharedWorld::new(world);
+    let mut players: BTreeMap<usize, (String, Sender<Option<String>>)> = BTreeMap::new();
+
+    for event in events {
+        match event {
+            Event::Join(id, name, replies) => {
+                let (name, text) = world.join(&name);
+                eprintln!("{} joined the shared world.", name);
+                replies.send(Some(text)).ok();
+                players.insert(id, (name, replies));
+            }
+            Event::Line(id, line) => {
+                if let Some((name, replies)) = players.get(&id).cloned() {
+                    let (text, playing) = world.play(&name, line);
+                    replies.send(Some(text)).ok();
+                    if !playing {
+                        replies.send(None).ok();
+                        players.remove(&id);
+                    }
+                }
+            }
+            Event::Leave(id) => {
+                if let Some((name, _)) = players.remove(&id) {
+                    world.leave(&name);
+                }
+            }
+        }
+
+        for (to, text) in world.take_news() {
+            if let Some((_, replies)) = players.values().find(|(name, _)| *name == to) {
+                replies.send(Some(format!("\n{}\n\n>> ", text))).ok();
+            }
+        }
+    }
+}
+
+/// A place taken by a connected player. It's given back when dropped, even if their game panics.
+struct Seat(Arc<AtomicUsize>);
+
+impl Drop for Seat {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Accept players until the server is stopped. Each player gets a thread of their own,
+/// and a game of their own, unless the world is shared.
+pub fn serve(listener: TcpListener, world: World, config: Config) {
+    let config = Arc::new(config);
+    let players = Arc::new(AtomicUsize::new(0));
+    let mut next_id = 0;
+
+    let events = if config.shared {
+        let (events, inbox) = channel::<Event>();
+        thread::spawn(move || run_world(world, inbox));
+        Some(events)
+    } else {
+        None
+    };
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Unable to accept a connection: {}", e);
+                continue;
+            }
+        };
+        let peer = stream
+            .peer_addr()
+            .map_or(String::from("unknown"), |a| a.to_string());
+
+        let taken = players.fetch_add(1, Ordering::SeqCst);
+        let seat = Seat(players.clone());
+        if taken >= config.max_players {
+            stream
+                .write_all(b"Sorry, the game is full. Try again later.\r\n")
+                .ok();
+            eprintln!("Turned away {}: too many players.", peer);
+            continue;
+        }
+
+        let (config, events) = (config.clone(), events.clone());
+        next_id += 1;
+        let id = next_id;
+        thread::spawn(move || {
+            let _seat = seat;
+            eprintln!("{} connected.", peer);
+            let result = match events {
+                Some(events) => connect(stream, id, events, &config),
+                None => play(stream, world, &config),
+            };
+            if let Err(e) = result {
+                eprintln!("Lost {}: {}", peer, e);
+            }
+            eprintln!("{} disconnected.", peer);
+        });
+    }
+}
+
+#[cfg(all(test, feature = "world"))]
+mod tests {
+    use super::*;
+    use crate::world;
+
+    /// Start a server on a free local port, and return its address.
+    fn start(config: Config) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || serve(listener, world::create_world, config));
+        address
+    }
+
+    fn read_all(stream: &mut TcpStream) -> String {
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let mut text = String::new();
+        stream.read_to_string(&mut text).ok();
+        text
+    }
+
+    #[test]
+    fn test_play_over_telnet() {
+        let address = start(Config::default());
+        let mut stream = TcpStream::connect(address).unwrap();
+
+        // A telnet client negotiates options before the player types anything.
+        stream.write_all(&[IAC, WILL, 1]).unwrap();
+        stream.write_all(b"score\r\nquit\r\n").unwrap();
+
+        let text = read_all(&mut stream);
+        assert!(text.contains("FOREST\r\n"));
+        assert!(text.contains("Your score is 0"));
+        assert!(text.contains("Goodbye!"));
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let address = start(Config {
+            max_players: 1,
+            ..Config::default()
+        });
+        let mut first = TcpStream::connect(&address).unwrap();
+        first.write_all(b"wait\r\n").unwrap();
+        // Wait for the first game to start, so it holds the only place.
+        let mut buf = [0u8; 16];
+        first.read_exact(&mut buf).unwrap();
+
+        let mut second = TcpStream::connect(&address).unwrap();
+        assert!(read_all(&mut second).contains("the game is full"));
+    }
+
+    #[test]
+    fn test_seat_given_back_on_panic() {
+        let players = Arc::new(AtomicUsize::new(1));
+        let seat = Seat(players.clone());
+        let game = thread::spawn(move || {
+            let _seat = seat;
+            panic!("the game fell over");
+        });
+        assert!(game.join().is_err());
+        assert_eq!(players.load(Ordering::SeqCst), 0);
+    }
+
+    /// Read until the text turns up, or there's nothing more to read.
+    fn read_until(stream: &mut TcpStream, text: &str) -> String {
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let mut received = String::new();
+        let mut buf = [0u8; 1024];
+        while !received.contains(text) {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => received.push_str(&String::from_utf8_lossy(&buf[..n])),
+            }
+        }
+        received
+    }
+
+    #[test]
+    fn test_shared_world() {
+        let address = start(Config {
+            shared: true,
+            ..Config::default()
+        });
+        let mut alice = TcpStream::connect(&address).unwrap();
+        alice.write_all(b"Alice\r\n").unwrap();
+        read_until(&mut alice, ">> ");
+
+        let mut bob = TcpStream::connect(&address).unwrap();
+        bob.write_all(b"Bob\r\n").unwrap();
+        assert!(read_until(&mut bob, ">> ").contains("Alice is here."));
+        assert!(read_until(&mut alice, ">> ").contains("Bob appears"));
+
+        alice
+            .write_all(b"kick leaves\r\ntake key\r\nquit\r\n")
+            .unwrap();
+        let text = read_until(&mut bob, "Alice vanishes.");
+        assert!(text.contains("Alice takes the key."));
+        assert!(text.contains("Alice vanishes."));
+    }
+
+    #[test]
+    fn test_idle_timeout() {
+        let address = start(Config {
+            idle: Duration::from_millis(200),
+            ..Config::default()
+        });
+        let mut stream = TcpStream::connect(address).unwrap();
+        assert!(read_all(&mut stream).contains("idle too long"));
+    }
+}
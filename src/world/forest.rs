@@ -0,0 +1,311 @@
+use crate::game::{
+    Action, Direction, Event, GameObject, GameRng, Health, Location, Notify, Puzzle, Weapon,
+};
+use crate::world::{garden::GARDEN, kitchen::KITCHEN};
+use rand::Rng;
+
+pub fn create(vec: &mut Vec<Box<dyn GameObject>>) {
+    vec.push(Box::new(Forest));
+    vec.push(Box::new(Leaves::new()));
+    vec.push(Box::new(Key::new()));
+    vec.push(Box::new(Raccoon::new()));
+}
+
+pub fn create_puzzles(vec: &mut Vec<Puzzle>) {
+    vec.push(
+        Puzzle::new(
+            "find the key",
+            5,
+            |_| true,
+            |atlas| atlas.loc_of(KEY.to_string()) != Some(LEAVES.to_string()),
+        )
+        .hint("The forest floor hides more than dirt.")
+        .hint("That pile of leaves looks like it has been disturbed.")
+        .hint("Try kicking the leaves."),
+    );
+}
+
+pub static FOREST: &str = "forest";
+pub static LEAVES: &str = "leaves";
+pub static KEY: &str = "key";
+pub static RACCOON: &str = "raccoon";
+
+#[derive(Default)]
+pub struct Forest;
+
+impl GameObject for Forest {
+    fn name(&self) -> String {
+        FOREST.to_string()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Go(Direction::North) | Action::Go(Direction::Exit) => true,
+            Action::Go(Direction::East) => true,
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Arrive(_) | Action::Leave(_) => true,
+            _ => false,
+        }
+    }
+
+    fn exits(&self) -> Vec<(Direction, String)> {
+        vec![
+            (Direction::North, KITCHEN.to_string()),
+            (Direction::Exit, KITCHEN.to_string()),
+            (Direction::East, GARDEN.to_string()),
+        ]
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Go(Direction::North) | Action::Go(Direction::Exit) => {
+                outln!("You follow the path north.");
+                Notify::Set(Location::To(KITCHEN.to_string()))
+            }
+            Action::Go(Direction::East) => {
+                outln!("You push through the brambles along an overgrown trail.");
+                Notify::Set(Location::To(GARDEN.to_string()))
+            }
+            Action::Describe(_) => {
+                outln!("You find yourself standing in a forest clearing, surrounded by trees. There is a path to the north, and an overgrown trail leads east.");
+                Notify::Handled
+            }
+            Action::Arrive(_) => {
+                outln!("The fog clears...");
+                Notify::Handled
+            }
+            Action::Leave(_) => {
+                outln!("The peaceful rustling leaves recede into the distance...");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("One of the trees nearby has been carved with the inscription: C+J. You wonder what it means.");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Leaves {
+    contains_key: bool,
+}
+
+impl Leaves {
+    pub fn new() -> Self {
+        Self { contains_key: true }
+    }
+}
+
+impl GameObject for Leaves {
+    fn name(&self) -> String {
+        LEAVES.to_string()
+    }
+
+    fn loc(&self) -> String {
+        FOREST.to_string()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Attack(_, _) => true,
+            Action::Take(_) => true,
+            _ => false,
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("There's a pile of leaves here.");
+                Notify::Handled
+            }
+            Action::Attack(_, _) => {
+                outln!("The leaves flutter and fly as you kick through them.");
+                if self.contains_key {
+                    self.contains_key = false;
+                    Notify::Move(KEY.to_string(), Location::Local)
+                } else {
+                    Notify::Handled
+                }
+            }
+            Action::Take(_) => {
+                outln!("You take a handful of leaves and throw them in the air. Feel better?");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Key {
+    loc: String,
+}
+
+impl Key {
+    pub fn new() -> Self {
+        Self {
+            loc: LEAVES.to_string(),
+        }
+    }
+}
+
+impl GameObject for Key {
+    fn name(&self) -> String {
+        KEY.to_string()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn set_loc(&mut self, loc: String) {
+        self.loc = loc;
+    }
+
+    fn portable(&self) -> bool {
+        true
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Take(_) => true,
+            _ => false,
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("A shiny key glints in the grass.");
+                Notify::Handled
+            }
+            Action::Take(_) => {
+                outln!("You take the key.");
+                Notify::Move(self.name(), Location::Inventory)
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+pub struct Raccoon {
+    loc: String,
+    health: Health,
+}
+
+impl Raccoon {
+    pub fn new() -> Self {
+        Self {
+            loc: FOREST.to_string(),
+            health: Health::new(6, 4),
+        }
+    }
+}
+
+impl Default for Raccoon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameObject for Raccoon {
+    fn name(&self) -> String {
+        RACCOON.to_string()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn set_loc(&mut self, loc: String) {
+        self.loc = loc;
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Attack(_, _) => true,
+            Action::Die => true,
+            _ => false,
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                if self.health.is_conscious() {
+                    outln!("A scruffy raccoon watches you from behind a tree.");
+                } else {
+                    outln!("A raccoon lies senseless in the grass.");
+                }
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                if self.health.is_wounded() {
+                    outln!("The raccoon is limping and glaring at you. It looks like it holds a grudge.");
+                } else {
+                    outln!("The raccoon has sharp little claws and a mask like a bandit. Best leave it alone.");
+                }
+                Notify::Handled
+            }
+            Action::Die => {
+                outln!("The raccoon lets out a final chitter and goes still. You monster.");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+
+    /// The raccoon won't sit still or keep quiet, whatever the defaults say.
+    fn has_default(&self, action: &Action) -> bool {
+        match action {
+            Action::Follow(_) | Action::Listen(_) => false,
+            _ => true,
+        }
+    }
+
+    fn observes(&self, event: &Event) -> bool {
+        match event {
+            Event::Left(_, room) => room == FOREST && self.health.is_conscious(),
+            _ => false,
+        }
+    }
+
+    /// The raccoon is glad to see you go.
+    fn observe(&mut self, _event: Event) -> Notify {
+        outln!("Behind you, the raccoon dives into the leaves you left behind.");
+        Notify::Handled
+    }
+
+    /// Now and then, the raccoon reminds you it's there.
+    fn tick(&mut self, rng: &mut GameRng) -> Notify {
+        if !self.health.is_conscious() || rng.gen_range(0..4) > 0 {
+            return Notify::Unhandled;
+        }
+        match rng.gen_range(0..3) {
+            0 => outln!("The raccoon rummages noisily through the leaves."),
+            1 => outln!("The raccoon chitters at you from a low branch."),
+            _ => outln!("The raccoon washes its paws and eyes you suspiciously."),
+        }
+        Notify::Handled
+    }
+
+    fn weapon(&self) -> Option<Weapon> {
+        Some(Weapon::new(1, 3, 50))
+    }
+
+    fn health(&self) -> Option<Health> {
+        Some(self.health)
+    }
+
+    fn set_health(&mut self, health: Health) {
+        self.health = health;
+    }
+}
@@ -0,0 +1,305 @@
+use crate::game::{Action, Direction, GameObject, Location, Notify, Puzzle};
+use crate::world::forest::FOREST;
+
+pub fn create(vec: &mut Vec<Box<dyn GameObject>>) {
+    vec.push(Box::new(Garden));
+    vec.push(Box::new(Gate::new()));
+    vec.push(Box::new(Orchard));
+}
+
+pub fn create_puzzles(vec: &mut Vec<Puzzle>) {
+    vec.push(
+        Puzzle::new(
+            "open the garden gate",
+            10,
+            |_| true,
+            |atlas| atlas.here() == ORCHARD,
+        )
+        .hint("The gate's keypad wants a code, three digits long.")
+        .hint("Someone has carved their initials into a tree in the forest.")
+        .hint("C is the 3rd letter of the alphabet, and J is the 10th. Type 310 on the keypad."),
+    );
+}
+
+pub static GARDEN: &str = "garden";
+pub static GATE: &str = "gate";
+pub static KEYPAD: &str = "keypad";
+pub static ORCHARD: &str = "orchard";
+
+/// The code for the gate. (C+J: the 3rd and 10th letters.)
+static CODE: &str = "310";
+
+pub struct Garden;
+
+impl GameObject for Garden {
+    fn name(&self) -> String {
+        GARDEN.to_string()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Go(Direction::West) | Action::Go(Direction::Exit) => true,
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Listen(_) => true,
+            _ => false,
+        }
+    }
+
+    fn exits(&self) -> Vec<(Direction, String)> {
+        vec![
+            (Direction::West, FOREST.to_string()),
+            (Direction::Exit, FOREST.to_string()),
+        ]
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Go(Direction::West) | Action::Go(Direction::Exit) => {
+                outln!("You push back through the brambles to the forest.");
+                Notify::Set(Location::To(FOREST.to_string()))
+            }
+            Action::Describe(_) => {
+                outln!("You are in an overgrown garden. Roses run wild over a high stone wall. The trail back to the forest is to the west.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("Whoever planted this garden stopped weeding it long ago. The wall is too high and too smooth to climb.");
+                Notify::Handled
+            }
+            Action::Listen(_) => {
+                outln!("Bees drone among the roses. Beyond the wall, a bird is singing.");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+/// The gate in the garden wall. It opens when the right code is entered on its keypad.
+#[derive(Default)]
+pub struct Gate {
+    display: String, // digits entered so far
+    attempts: u32,   // wrong codes entered
+    open: bool,
+}
+
+impl Gate {
+    pub fn new() -> Self {
+        Self {
+            display: String::new(),
+            attempts: 0,
+            open: false,
+        }
+    }
+
+    /// The keypad display: "[3 1 _]"
+    fn display(&self) -> String {
+        let digits: Vec<String> = (0..CODE.len())
+            .map(|i| match self.display.chars().nth(i) {
+                Some(c) => c.to_string(),
+                None => String::from("_"),
+            })
+            .collect();
+        format!("[{}]", digits.join(" "))
+    }
+
+    /// Press the keys, one at a time. The code is checked when the display is full.
+    fn press(&mut self, keys: String) -> Notify {
+        if self.open {
+            outln!("The gate is already open.");
+            return Notify::Handled;
+        }
+        if keys == "clear" || keys == "c" {
+            self.display.clear();
+            outln!("The display goes blank: {}", self.display());
+            return Notify::Handled;
+        }
+        if keys.is_empty() || !keys.chars().all(|c| c.is_ascii_digit()) {
+            outln!("The keypad only has the digits 0 to 9, and a CLEAR button.");
+            return Notify::Handled;
+        }
+
+        // Typing a whole code starts afresh. Pressing one key adds to the display.
+        if keys.len() > 1 && keys.len() != CODE.len() {
+            outln!("The code is {} digits long.", CODE.len());
+            return Notify::Handled;
+        }
+        if keys.len() > 1 {
+            self.display.clear();
+        }
+        for key in keys.chars() {
+            self.display.push(key);
+            if self.display.len() < CODE.len() {
+                continue;
+            }
+            if self.display == CODE {
+                outln!(
+                    "{} The keypad chimes, and with a clank the gate swings open.",
+                    self.display()
+                );
+                self.display.clear();
+                self.open = true;
+                return Notify::Handled;
+            }
+            self.attempts += 1;
+            outln!(
+                "{} The keypad buzzes angrily, and the display goes blank.",
+                self.display()
+            );
+            self.display.clear();
+        }
+        if !self.display.is_empty() {
+            outln!("Beep. {}", self.display());
+        }
+        Notify::Handled
+    }
+}
+
+impl GameObject for Gate {
+    fn name(&self) -> String {
+        GATE.to_string()
+    }
+
+    fn aliases(&self) -> Vec<String> {
+        vec![KEYPAD.to_string()]
+    }
+
+    fn loc(&self) -> String {
+        GARDEN.to_string()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Open(_, _) => true,
+            Action::Press(_, _) => true,
+            Action::Go(Direction::North) | Action::Go(Direction::Enter) => true,
+            _ => false,
+        }
+    }
+
+    fn exits(&self) -> Vec<(Direction, String)> {
+        if self.open {
+            vec![(Direction::North, ORCHARD.to_string())]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                if self.open {
+                    outln!("An iron gate in the north wall stands open.");
+                } else {
+                    outln!(
+                        "An iron gate in the north wall is shut tight. There's a keypad beside it."
+                    );
+                }
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("The gate is wrought iron, twisted into vines and roses. A brass keypad with the digits 0 to 9 is set into the gatepost.");
+                outln!("The keypad's little display reads {}.", self.display());
+                if self.attempts > 0 {
+                    outln!(
+                        "Someone has scratched a tally beside it: {} failed attempt(s).",
+                        self.attempts
+                    );
+                }
+                Notify::Handled
+            }
+            Action::Open(_, _) => {
+                if self.open {
+                    outln!("It's already open.");
+                } else {
+                    outln!("The gate won't budge. The keypad blinks expectantly.");
+                }
+                Notify::Handled
+            }
+            Action::Press(keys, _) => self.press(keys),
+            Action::Go(Direction::North) | Action::Go(Direction::Enter) => {
+                if self.open {
+                    outln!("You step through the gate.");
+                    Notify::Set(Location::To(ORCHARD.to_string()))
+                } else {
+                    outln!("The gate is shut tight.");
+                    Notify::Handled
+                }
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+pub struct Orchard;
+
+impl GameObject for Orchard {
+    fn name(&self) -> String {
+        ORCHARD.to_string()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Go(Direction::South) | Action::Go(Direction::Exit) => true,
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            _ => false,
+        }
+    }
+
+    fn exits(&self) -> Vec<(Direction, String)> {
+        vec![
+            (Direction::South, GARDEN.to_string()),
+            (Direction::Exit, GARDEN.to_string()),
+        ]
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Go(Direction::South) | Action::Go(Direction::Exit) => {
+                outln!("You go back through the gate.");
+                Notify::Set(Location::To(GARDEN.to_string()))
+            }
+            Action::Describe(_) => {
+                outln!("You are in a walled orchard. Old apple trees lean together, heavy with fruit. The gate to the garden is to the south.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("The grass between the trees is cropped short. Somebody still comes here.");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gate_code() {
+        let mut gate = Gate::new();
+        assert!(gate.exits().is_empty());
+
+        gate.act(Action::Press(String::from("1234"), None));
+        assert_eq!(gate.attempts, 0);
+        assert_eq!(gate.display(), "[_ _ _]");
+
+        gate.act(Action::Press(String::from("1"), None));
+        gate.act(Action::Press(String::from("123"), None));
+        assert_eq!(gate.attempts, 1);
+        assert_eq!(gate.display(), "[_ _ _]");
+
+        gate.act(Action::Press(String::from("clear"), None));
+        gate.act(Action::Press(String::from("3"), None));
+        gate.act(Action::Press(String::from("1"), None));
+        assert!(!gate.open);
+        gate.act(Action::Press(String::from("0"), None));
+        assert!(gate.open);
+        assert_eq!(gate.exits(), vec![(Direction::North, ORCHARD.to_string())]);
+    }
+}
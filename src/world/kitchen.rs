@@ -0,0 +1,844 @@
+use crate::game::{
+    kind_of, Action, Container, Direction, Effect, Event, GameAtlas, GameObject, Location,
+    Lockable, Notify, Puzzle, Weapon,
+};
+use crate::mini::{guess::Guess, Outcome};
+use crate::world::forest::{FOREST, KEY, LEAVES};
+
+pub fn create(vec: &mut Vec<Box<dyn GameObject>>) {
+    vec.push(Box::new(Kitchen::new()));
+    vec.push(Box::new(Door::new()));
+    vec.push(Box::new(Sink::new()));
+    vec.push(Box::new(Counter::new()));
+    vec.push(Box::new(Knife::new()));
+    vec.push(Box::new(BreadBox::new()));
+    vec.push(Box::new(Bread::new()));
+    vec.push(Box::new(GoldRing::new()));
+    vec.push(Box::new(Slate::new()));
+}
+
+/// Register the kinds of objects that are made during the game.
+pub fn register(atlas: &mut GameAtlas) {
+    atlas.register(HALF, |name| Box::new(BreadHalf::new(name)));
+}
+
+pub fn create_puzzles(vec: &mut Vec<Puzzle>) {
+    vec.push(
+        Puzzle::new(
+            "unlock the breadbox",
+            10,
+            |atlas| atlas.loc_of(KEY.to_string()) != Some(LEAVES.to_string()),
+            is_unlocked,
+        )
+        .hint("Something rattles inside the breadbox.")
+        .hint("A locked breadbox needs a key. Didn't you find one somewhere?")
+        .hint("Open the breadbox with the key, and take what's inside."),
+    );
+    vec.push(
+        Puzzle::new("cut the bread", 10, is_unlocked, |atlas| {
+            atlas
+                .loc_of(GOLDRING.to_string())
+                .is_some_and(|loc| loc != BREAD)
+        })
+        .hint("That loaf is awfully heavy for bread.")
+        .hint("The bread is too hard to break by hand. Perhaps something sharp?")
+        .hint("Look in the sink for a knife, then cut the bread with the knife."),
+    );
+}
+
+/// Has the breadbox been unlocked? Puzzles ask about things that are there, not missing ones.
+fn is_unlocked(atlas: &GameAtlas) -> bool {
+    atlas
+        .get_lockable(BREADBOX.to_string())
+        .is_some_and(|lock| !lock.locked)
+}
+
+pub static KITCHEN: &str = "kitchen";
+pub static DOOR: &str = "door";
+pub static SINK: &str = "sink";
+pub static COUNTER: &str = "counter";
+pub static KNIFE: &str = "knife";
+pub static BREADBOX: &str = "breadbox";
+pub static BREAD: &str = "bread";
+pub static GOLDRING: &str = "gold ring";
+pub static SLATE: &str = "slate";
+/// Half a loaf, made when the bread is cut. There can be any number of them.
+pub static HALF: &str = "half";
+
+#[derive(Default)]
+pub struct Kitchen {
+    name: String,
+}
+
+impl Kitchen {
+    pub fn new() -> Self {
+        Self {
+            name: KITCHEN.to_string(),
+        }
+    }
+}
+
+impl GameObject for Kitchen {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Go(Direction::Exit) => true,
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Listen(_) => true,
+            Action::Leave(_) => true,
+            _ => false,
+        }
+    }
+
+    fn exits(&self) -> Vec<(Direction, String)> {
+        vec![(Direction::Exit, FOREST.to_string())]
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("You are in a kitchen. The dishes are piled in the sink. The refrigerator is empty. There is a breadbox on the counter.");
+                Notify::Handled
+            }
+            Action::Listen(_) => {
+                outln!("You hear the faint buzzing of flies and a slow drip into the sink.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("You are in a kitchen. It's a mess. Whoever lives here is a slob.");
+                Notify::Handled
+            }
+            Action::Leave(_) => {
+                outln!("You head toward fresher air.");
+                Notify::Handled
+            }
+            Action::Go(Direction::Exit) => {
+                outln!("It's better alternative than doing the dishes.");
+                Notify::Set(Location::To(FOREST.to_string()))
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Sink {
+    name: String,
+    loc: String,
+    holds_knife: bool,
+}
+
+impl Sink {
+    pub fn new() -> Self {
+        Self {
+            name: SINK.to_string(),
+            loc: KITCHEN.to_string(),
+            holds_knife: true,
+        }
+    }
+}
+
+impl GameObject for Sink {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Drop(_, Some(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("A sink full of dirty dishes.");
+                Notify::Handled
+            }
+            // Anything small enough goes down the drain. (Only what you carry gets this far.)
+            Action::Drop(item, Some(_)) if item == GOLDRING => {
+                Notify::Effects(vec![
+                    Effect::Print(String::from(
+                        "The ring slips between the dishes, circles the drain once, and is gone. Plink.",
+                    )),
+                    Effect::Destroy(GOLDRING.to_string()),
+                ])
+            }
+            Action::Drop(_, Some(_)) => {
+                outln!("There's no room in the sink, what with all the dirty dishes.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                if self.holds_knife {
+                    outln!("The dishes are covered in mold and a milky slime. Wait... is that a knife?");
+                    Notify::Move("knife".to_string(), Location::Local)
+                } else {
+                    outln!("The dishes are covered in mold and a milky slime. Gross.");
+                    Notify::Handled
+                }
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+
+    fn observes(&self, event: &Event) -> bool {
+        match event {
+            Event::Arrived(_, room) => *room == self.loc,
+            _ => false,
+        }
+    }
+
+    /// The tap drips to greet you.
+    fn observe(&mut self, _event: Event) -> Notify {
+        outln!("Plink. A drop of water falls from the tap into the sink.");
+        Notify::Handled
+    }
+}
+
+/// The kitchen door, at the end of the path from the forest. It's in both rooms at once,
+/// so it's the same door, open or locked, whichever side you're on.
+#[derive(Default)]
+pub struct Door {
+    lock: Lockable,
+}
+
+impl Door {
+    pub fn new() -> Self {
+        Self {
+            lock: Lockable {
+                keys: vec![KEY.to_string()],
+                ..Lockable::new(false)
+            },
+        }
+    }
+}
+
+impl GameObject for Door {
+    fn name(&self) -> String {
+        DOOR.to_string()
+    }
+
+    fn loc(&self) -> String {
+        FOREST.to_string()
+    }
+
+    fn sides(&self) -> Vec<String> {
+        vec![FOREST.to_string(), KITCHEN.to_string()]
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Open(_, _) => true,
+            Action::Close(_, _) => true,
+            Action::Lock(_, _) => true,
+            Action::Unlock(_, _) => true,
+            Action::Knock(_) => true,
+            _ => false,
+        }
+    }
+
+    fn lockable(&self) -> Option<Lockable> {
+        Some(self.lock.clone())
+    }
+
+    fn set_lockable(&mut self, lockable: Lockable) {
+        self.lock = lockable;
+    }
+
+    /// The game opens, closes, locks and unlocks the door, and won't let you through while it's locked.
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("A weathered wooden door. It's {}.", self.lock.state());
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!(
+                    "The paint is peeling, and there's a keyhole under the latch. It's {}.",
+                    self.lock.state()
+                );
+                Notify::Handled
+            }
+            Action::Knock(_) if self.lock.open => {
+                outln!("You knock on the open door. It creaks, and swings a little wider.");
+                Notify::Handled
+            }
+            Action::Knock(_) => {
+                outln!("Knock, knock. Nobody's there. Nobody has been for a long time.");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+/// Things can be put on the counter, and they stay where you can see them.
+#[derive(Default)]
+pub struct Counter {
+    name: String,
+    loc: String,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Self {
+            name: COUNTER.to_string(),
+            loc: KITCHEN.to_string(),
+        }
+    }
+}
+
+impl GameObject for Counter {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            _ => false,
+        }
+    }
+
+    fn container(&self) -> Option<Container> {
+        Some(Container::surface(4))
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("A long counter, sticky with crumbs.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("Nobody has wiped this counter in years. There's room to put things down, if you must.");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Knife {
+    name: String,
+    loc: String,
+}
+
+impl Knife {
+    pub fn new() -> Self {
+        Self {
+            name: KNIFE.to_string(),
+            loc: SINK.to_string(),
+        }
+    }
+}
+
+impl GameObject for Knife {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn set_loc(&mut self, loc: String) {
+        self.loc = loc;
+    }
+
+    fn portable(&self) -> bool {
+        true
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Take(_) => true,
+            Action::Use(_, _) => true,
+            Action::Attack(_, _) => true,
+            _ => false,
+        }
+    }
+
+    fn weapon(&self) -> Option<Weapon> {
+        Some(Weapon::new(2, 4, 80))
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("A rusty knife.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("It won't slay a dragon, but it might work on bread.");
+                Notify::Handled
+            }
+            Action::Take(_) => {
+                outln!(
+                    "You reach in gingerly and take the knife, barely resisting the urge to vomit."
+                );
+                Notify::Move(self.name(), Location::Inventory)
+            }
+            Action::Use(target, _) | Action::Attack(target, _) => {
+                if target.as_str() == BREAD {
+                    // The bread knows what happens when it's cut.
+                    Notify::Unhandled
+                } else {
+                    outln!("Are you serious? You can't use a knife on that.");
+                    Notify::Handled
+                }
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BreadBox {
+    name: String,
+    loc: String,
+    lock: Lockable,
+}
+
+impl BreadBox {
+    pub fn new() -> Self {
+        Self {
+            name: BREADBOX.to_string(),
+            loc: KITCHEN.to_string(),
+            lock: Lockable::locked(&[KEY]),
+        }
+    }
+}
+
+impl GameObject for BreadBox {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Open(_, _) => true,
+            Action::Close(_, _) => true,
+            Action::Lock(_, _) => true,
+            Action::Unlock(_, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Room for one loaf, when it's open.
+    fn container(&self) -> Option<Container> {
+        Some(Container::new(1, self.lock.open))
+    }
+
+    fn lockable(&self) -> Option<Lockable> {
+        Some(self.lock.clone())
+    }
+
+    fn set_lockable(&mut self, lockable: Lockable) {
+        self.lock = lockable;
+    }
+
+    /// The game opens, closes, locks and unlocks the breadbox. (See Game::operate.)
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("A breadbox. It's {}.", self.lock.state());
+                Notify::Handled
+            }
+            Action::Examine(_) if self.lock.locked => {
+                outln!("You give the breadbox a shake and something heavy and hard rattles inside.\nUnfortunately, you can't see what it is because the breadbox is locked.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!(
+                    "It's a dented tin breadbox, with a little keyhole in the lid. It's {}.",
+                    self.lock.state()
+                );
+                Notify::Handled
+            }
+            Action::Open(_, None) if self.lock.locked => {
+                outln!("You try to open the breadbox, but it's locked.\nWhat kind of person locks a breadbox?");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Bread {
+    name: String,
+    loc: String,
+}
+
+impl Bread {
+    pub fn new() -> Self {
+        Self {
+            name: BREAD.to_string(),
+            loc: BREADBOX.to_string(),
+        }
+    }
+}
+
+impl GameObject for Bread {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn set_loc(&mut self, loc: String) {
+        self.loc = loc;
+    }
+
+    fn portable(&self) -> bool {
+        true
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Attack(_, _) => true,
+            Action::Use(_, _) => true,
+            _ => false,
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("A crusty loaf of bread.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("The crust is so dry and hard that you'd break a tooth trying to eat it.");
+                Notify::Handled
+            }
+            Action::Attack(_, Some(knife)) if knife == KNIFE => {
+                outln!("You hack the crusty loaf clean in two. Take that you vile loaf!!");
+                // The halves are left where the loaf was, but the ring falls out.
+                Notify::Effects(vec![
+                    Effect::Spawn(HALF.to_string(), Location::To(self.loc.clone())),
+                    Effect::Spawn(HALF.to_string(), Location::To(self.loc.clone())),
+                    Effect::Move(GOLDRING.to_string(), Location::Local),
+                    Effect::Destroy(self.name()),
+                ])
+            }
+            Action::Attack(_, attacker) => {
+                match attacker {
+                    None => outln!("You punch the bread and scrape your knuckles badly. Ouch!"),
+                    Some(attacker) => outln!("The loaf resists the {}.", kind_of(&attacker)),
+                }
+                Notify::Handled
+            }
+            Action::Use(_, knife) => {
+                match knife {
+                    None => outln!("You try to use the bread, but it's too crusty."),
+                    Some(knife) => outln!("You use the {} on the bread, but it's too crusty. Maybe try again with a little more force?", kind_of(&knife)),
+                }
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+/// Half a loaf of bread. Cutting the bread makes two. (See register.)
+pub struct BreadHalf {
+    name: String, // unique, e.g. "half#1"
+    loc: String,
+}
+
+impl BreadHalf {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            loc: String::new(),
+        }
+    }
+}
+
+impl GameObject for BreadHalf {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn aliases(&self) -> Vec<String> {
+        vec![HALF.to_string(), BREAD.to_string()]
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn set_loc(&mut self, loc: String) {
+        self.loc = loc;
+    }
+
+    fn portable(&self) -> bool {
+        true
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            _ => false,
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("Half a loaf of bread.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("Stale, hard, and hollowed out in the middle. Good for nothing but feeding ducks.");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct GoldRing {
+    name: String,
+    loc: String,
+    seen: bool,
+    worn: bool, // on your pinky, which makes you intangible
+}
+
+impl GoldRing {
+    pub fn new() -> Self {
+        Self {
+            name: GOLDRING.to_string(),
+            loc: BREAD.to_string(),
+            seen: false,
+            worn: false,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl GameObject for GoldRing {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn set_loc(&mut self, loc: String) {
+        self.loc = loc;
+    }
+
+    fn portable(&self) -> bool {
+        true
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Take(_) => true,
+            Action::Use(_, None) => true,
+            Action::Wear(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Typed one word at a time, "take gold ring" asks for the gold.
+    fn aliases(&self) -> Vec<String> {
+        vec![String::from("ring"), String::from("gold")]
+    }
+
+    fn observes(&self, event: &Event) -> bool {
+        match event {
+            Event::Dropped(_, object) => self.worn && *object == self.name,
+            _ => false,
+        }
+    }
+
+    /// However the ring leaves your hand, it comes off your pinky first.
+    fn observe(&mut self, _event: Event) -> Notify {
+        self.worn = false;
+        outln!("(first taking the ring off your pinky)");
+        Notify::Handled
+    }
+
+    /// While you wear the ring, your hands pass through everything but the ring itself.
+    fn before(&mut self, action: &Action) -> Notify {
+        match action {
+            Action::Take(Some(name)) if self.worn && *name == self.name => {
+                Notify::Instead(Action::Use(self.name(), None))
+            }
+            Action::Take(Some(name)) if self.worn => {
+                outln!(
+                    "Your fingers pass right through the {}. The ring has made you intangible.",
+                    kind_of(name)
+                );
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+
+    fn after(&mut self, action: &Action) -> Notify {
+        match action {
+            Action::Examine(Some(_)) if self.worn => {
+                outln!("Through the ring's haze, it all seems faint and far away.");
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) if self.worn => {
+                outln!("A gold ring glimmers on your pinky.");
+                Notify::Handled
+            }
+            Action::Describe(_) => {
+                if !self.seen {
+                    outln!("A gold ring, barely big enough for your pinky finger, falls onto the counter with clear tinkling sound.");
+                    self.seen = true;
+                } else {
+                    outln!("A gold ring, barely big enough for your pinky finger.");
+                }
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("It's a pretty, albeit small, gold ring.");
+                Notify::Handled
+            }
+            Action::Take(_) => {
+                outln!("You slip the ring into your pocket.");
+                Notify::Move(self.name(), Location::Inventory)
+            }
+            Action::Use(_, None) if self.worn => {
+                self.worn = false;
+                outln!("You pull the ring off your pinky, and the world is solid again.");
+                Notify::Handled
+            }
+            Action::Wear(_) if self.worn => {
+                outln!("You're already wearing it.");
+                Notify::Handled
+            }
+            Action::Use(_, None) | Action::Wear(_) => {
+                self.worn = true;
+                outln!(
+                    "You slip the ring onto your pinky. The world turns pale and thin around you."
+                );
+                Notify::Move(self.name(), Location::Inventory)
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Slate {
+    name: String,
+    loc: String,
+    wins: u32, // words guessed
+}
+
+impl Slate {
+    pub fn new() -> Self {
+        Self {
+            name: SLATE.to_string(),
+            loc: KITCHEN.to_string(),
+            wins: 0,
+        }
+    }
+}
+
+impl GameObject for Slate {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn loc(&self) -> String {
+        self.loc.clone()
+    }
+
+    fn can_do(&self, action: &Action) -> bool {
+        match action {
+            Action::Describe(_) => true,
+            Action::Examine(_) => true,
+            Action::Read(_, _) => true,
+            Action::Use(_, _) => true,
+            Action::Played(_, _) => true,
+            _ => false,
+        }
+    }
+
+    fn act(&mut self, action: Action) -> Notify {
+        match action {
+            Action::Describe(_) => {
+                outln!("A slate hangs by the door, with a word game chalked on it.");
+                Notify::Handled
+            }
+            Action::Examine(_) => {
+                outln!("Someone has chalked a row of blanks and a little gallows. A stub of chalk hangs from a string.");
+                if self.wins > 0 {
+                    outln!("There are {} tick(s) beside the gallows.", self.wins);
+                }
+                Notify::Handled
+            }
+            Action::Read(_, _) | Action::Use(_, _) => {
+                outln!("You pick up the chalk.");
+                Notify::Play(Box::new(Guess::new()))
+            }
+            Action::Played(_, outcome) => {
+                match outcome {
+                    Outcome::Won => {
+                        self.wins += 1;
+                        outln!("You chalk a tick beside the gallows.");
+                    }
+                    Outcome::Lost => outln!("You wipe the slate clean, muttering."),
+                    Outcome::Aborted => outln!("You let the chalk drop on its string."),
+                }
+                Notify::Handled
+            }
+            _ => Notify::Unhandled,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! The example world played by the binary: a forest, a garden and a kitchen.
+//!
+//! ```
+//! use zork_rust::{world, Game};
+//!
+//! let mut game = Game::new(world::create_world);
+//! let result = game.step("kick leaves");
+//! assert!(result.output.contains("leaves"));
+//! assert!(!result.ended);
+//! ```
+// Content objects answer can_do with a match, one arm per action they handle.
+#![allow(clippy::match_like_matches_macro)]
+
+use crate::game::{GameAtlas, GameObject, Puzzle};
+
+pub mod forest;
+pub mod garden;
+pub mod kitchen;
+
+pub use forest::{Forest, Key, Leaves};
+pub use kitchen::{Bread, BreadBox, Kitchen, Knife, Sink};
+
+/// Build the world from scratch: every object in its starting place, and the puzzles to solve.
+/// ! Restoring a saved game calls this again, so it must always build the same world.
+pub fn create_world() -> (GameAtlas, Vec<Puzzle>) {
+    let mut vec = Vec::new() as Vec<Box<dyn GameObject>>;
+    forest::create(&mut vec);
+    garden::create(&mut vec);
+    kitchen::create(&mut vec);
+
+    let mut atlas = GameAtlas::new(String::from(forest::FOREST));
+    atlas.add_all(vec);
+    kitchen::register(&mut atlas);
+
+    let mut puzzles = Vec::new() as Vec<Puzzle>;
+    forest::create_puzzles(&mut puzzles);
+    garden::create_puzzles(&mut puzzles);
+    kitchen::create_puzzles(&mut puzzles);
+
+    (atlas, puzzles)
+}